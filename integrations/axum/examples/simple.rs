@@ -1,7 +1,7 @@
 use std::net::SocketAddr;
 
 use axum::{response::IntoResponse, routing::get, Router};
-use rstml_component::{html, write_html, For, HtmlComponent, HtmlContent};
+use rstml_component::{html, static_html, write_html, For, HtmlComponent, HtmlContent, StaticHtml};
 use rstml_component_axum::HtmlContentAxiosExt;
 use tokio::net::TcpListener;
 
@@ -45,14 +45,28 @@ async fn index() -> impl IntoResponse {
 	.into_html()
 }
 
+// Fully static pages are served without copying the HTML
+const ABOUT: StaticHtml = static_html!(
+	<div class="about">
+		<h1>"About"</h1>
+		<p>"A list of books."</p>
+	</div>
+);
+
+async fn about() -> impl IntoResponse {
+	ABOUT.into_html()
+}
+
 #[tokio::main]
 async fn main() {
-	let app = Router::new().route("/", get(index));
+	let app = Router::new()
+		.route("/", get(index))
+		.route("/about", get(about));
 
 	let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
 	let listener = TcpListener::bind(addr)
 		.await
-		.expect(&format!("Failed to bind a TcpListener to {}", addr));
+		.unwrap_or_else(|_| panic!("Failed to bind a TcpListener to {}", addr));
 	println!("listening on {}", addr);
 
 	axum::serve(listener, app.into_make_service())
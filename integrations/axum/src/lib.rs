@@ -2,9 +2,22 @@
 // the `docsrs` configuration attribute is defined
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
-use axum::{http::StatusCode, response::IntoResponse};
+use axum::{
+	body::Body,
+	extract::FromRequestParts,
+	http::{request::Parts, StatusCode},
+	response::IntoResponse,
+};
 use axum_extra::{headers::ContentType, TypedHeader};
-use rstml_component::{HtmlContent, HtmlFormatter};
+use bytes::Bytes;
+use futures_core::Stream;
+use rstml_component::{AsyncRender, ContextStore, HtmlContent, HtmlFormatter};
+use std::{
+	convert::Infallible,
+	future::Future,
+	pin::Pin,
+	task::{ready, Context, Poll},
+};
 
 pub struct Html<C>(pub C);
 
@@ -29,15 +42,168 @@ impl<C: HtmlContent> HtmlContent for Html<C> {
 	}
 }
 
+/// Writes the content to the response body. The body of a
+/// [StaticHtml](rstml_component::StaticHtml) uses `Bytes::from_static`, so it is not copied.
+///
+/// The body is buffered: when the content contains async content, such as `async` components,
+/// the complete output is rendered before any of it is sent. Use [StreamingHtml] to stream the
+/// body instead.
+///
+/// When rendering the content fails, the response is a bare 500. `IntoResponse` can't await the
+/// async content, so when rendering it fails, the status has already been set and the body
+/// fails instead. [into_response_async](Self::into_response_async) awaits the async content
+/// first, so it responds with a 500 for these errors as well.
 impl<C: HtmlContent> IntoResponse for Html<C> {
 	fn into_response(self) -> axum::response::Response {
-		match self.0.into_bytes() {
-			Ok(bytes) => (TypedHeader(ContentType::html()), bytes).into_response(),
-			Err(_e) => (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response(),
+		let render = match self.0.render_async() {
+			Ok(render) => render,
+			Err(_e) => return internal_server_error(),
+		};
+
+		let body = match render.into_rendered() {
+			Ok(bytes) => Body::from(bytes),
+			Err(render) => Body::from_stream(BufferedStream(Some(Box::pin(render.into_bytes())))),
+		};
+
+		(TypedHeader(ContentType::html()), body).into_response()
+	}
+}
+
+impl<C: HtmlContent> Html<C> {
+	/// Renders the content, awaiting the async content it contains, and creates the response
+	/// with the complete output, or a bare 500 when rendering any of it fails.
+	pub async fn into_response_async(self) -> axum::response::Response {
+		let render = async { self.0.render_async()?.into_bytes().await };
+		match render.await {
+			Ok(bytes) => (TypedHeader(ContentType::html()), Body::from(bytes)).into_response(),
+			Err(_e) => internal_server_error(),
 		}
 	}
 }
 
+fn internal_server_error() -> axum::response::Response {
+	(StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+}
+
+/// The complete output of an [AsyncRender], from [AsyncRender::into_bytes].
+type RenderedBytes = Pin<Box<dyn Future<Output = Result<Bytes, std::fmt::Error>> + Send>>;
+
+/// Returns the complete output of an [AsyncRender] as a single chunk.
+struct BufferedStream(Option<RenderedBytes>);
+
+impl Stream for BufferedStream {
+	type Item = Result<Bytes, std::fmt::Error>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let Some(render) = &mut self.0 else {
+			return Poll::Ready(None);
+		};
+
+		let output = ready!(render.as_mut().poll(cx));
+		self.0 = None;
+		Poll::Ready(Some(output))
+	}
+}
+
+/// Streams the output of an [AsyncRender].
+struct AsyncStream(AsyncRender);
+
+impl Stream for AsyncStream {
+	type Item = Result<Bytes, std::fmt::Error>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		self.0.poll_chunk(cx)
+	}
+}
+
+/// A response that streams the body, so the content of [Suspense](rstml_component::Suspense)
+/// components doesn't hold up the rest of the page.
+///
+/// The page is rendered first, with the fallback of each [Suspense](rstml_component::Suspense)
+/// component in place, and async content such as `async` components awaited in document order.
+/// The response then drives the pending futures, and streams the content of each
+/// as it resolves. When rendering the page fails, the response is a bare 500. Once the response
+/// is sent, failing to render content ends the body early, so content that may fail is best
+/// wrapped in an [ErrorBoundary](rstml_component::ErrorBoundary).
+pub struct StreamingHtml<C>(pub C);
+
+impl<C> StreamingHtml<C>
+where
+	C: FnOnce(&mut HtmlFormatter) -> std::fmt::Result,
+{
+	pub fn from_fn(f: C) -> Self {
+		StreamingHtml(f)
+	}
+}
+
+impl<C: HtmlContent> From<C> for StreamingHtml<C> {
+	fn from(value: C) -> Self {
+		Self(value)
+	}
+}
+
+impl<C: HtmlContent> IntoResponse for StreamingHtml<C> {
+	fn into_response(self) -> axum::response::Response {
+		let body = match self.0.render_streaming().map(AsyncRender::into_rendered) {
+			Ok(Ok(bytes)) => Body::from(bytes),
+			Ok(Err(render)) => Body::from_stream(AsyncStream(render)),
+			Err(_e) => return internal_server_error(),
+		};
+
+		(TypedHeader(ContentType::html()), body).into_response()
+	}
+}
+
+/// Extracts the context values for rendering from the [ContextStore] in the request extensions,
+/// or no values when there is none.
+///
+/// Middleware can add request-scoped values, such as the current user or the locale, to the
+/// store in the request extensions:
+///
+/// ```
+/// # use axum::{extract::Request, middleware::Next, response::Response};
+/// # use rstml_component::ContextStore;
+/// struct Locale(String);
+///
+/// async fn locale(mut request: Request, next: Next) -> Response {
+///     let locale = Locale(String::from("en"));
+///     request.extensions_mut().get_or_insert_default::<ContextStore>().insert(locale);
+///     next.run(request).await
+/// }
+/// ```
+///
+/// The handler then renders the response with the values through [render](Self::render) or
+/// [stream](Self::stream), so that components can read them through
+/// [HtmlFormatter::context] or `#[context]` arguments.
+#[derive(Clone, Debug, Default)]
+pub struct HtmlContext(pub ContextStore);
+
+impl HtmlContext {
+	/// Creates an [Html] response that renders the content with the context values.
+	pub fn render(self, content: impl HtmlContent) -> Html<impl HtmlContent> {
+		Html(move |formatter: &mut HtmlFormatter| formatter.with_context(self.0, content))
+	}
+
+	/// Creates a [StreamingHtml] response that renders the content with the context values.
+	pub fn stream(self, content: impl HtmlContent) -> StreamingHtml<impl HtmlContent> {
+		StreamingHtml(move |formatter: &mut HtmlFormatter| formatter.with_context(self.0, content))
+	}
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for HtmlContext {
+	type Rejection = Infallible;
+
+	async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+		Ok(Self(
+			parts
+				.extensions
+				.get::<ContextStore>()
+				.cloned()
+				.unwrap_or_default(),
+		))
+	}
+}
+
 pub trait HtmlContentAxiosExt: Sized {
 	fn into_html(self) -> Html<Self>;
 
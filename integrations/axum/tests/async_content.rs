@@ -0,0 +1,81 @@
+use axum::{body::to_bytes, response::IntoResponse};
+use futures_core::Stream;
+use rstml_component::{component, html, write_html, ErrorBoundary, HtmlContent, HtmlFormatter};
+use rstml_component_axum::Html;
+use std::{future::poll_fn, pin::Pin};
+
+#[component(Greeting)]
+async fn greeting(name: &'static str) -> impl HtmlContent {
+	tokio::task::yield_now().await;
+	html!(<p>"Hello, "{name}</p>)
+}
+
+#[tokio::test]
+async fn awaits_async_components() {
+	let response = Html(html!(<main><Greeting name="async" /></main>)).into_response();
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+	assert_eq!(body, "<main><p>Hello, async</p></main>");
+}
+
+#[component(Broken)]
+async fn broken() -> impl HtmlContent {
+	fn broken(_: &mut HtmlFormatter) -> std::fmt::Result {
+		Err(std::fmt::Error)
+	}
+
+	tokio::task::yield_now().await;
+	html!(<p>{broken}</p>)
+}
+
+#[tokio::test]
+async fn async_errors_caught_by_error_boundary() {
+	let response = Html(html!(
+		<main>
+			<ErrorBoundary fallback={|f, _| write_html!(f, <p>"Unavailable"</p>)}>
+				<Broken />
+			</ErrorBoundary>
+			<footer />
+		</main>
+	))
+	.into_response();
+
+	assert_eq!(response.status(), 200);
+
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	assert_eq!(body, "<main><p>Unavailable</p><footer></footer></main>");
+}
+
+#[tokio::test]
+async fn buffers_the_body() {
+	let response =
+		Html(html!(<main><Greeting name="one" /><Greeting name="two" /></main>)).into_response();
+	let mut body = response.into_body().into_data_stream();
+
+	let chunk = poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).await;
+	assert_eq!(
+		chunk.unwrap().unwrap(),
+		"<main><p>Hello, one</p><p>Hello, two</p></main>"
+	);
+
+	let chunk = poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).await;
+	assert!(chunk.is_none());
+}
+
+#[tokio::test]
+async fn async_errors_respond_with_500() {
+	let response = Html(html!(<main><Broken /></main>))
+		.into_response_async()
+		.await;
+
+	assert_eq!(response.status(), 500);
+
+	let response = Html(html!(<main><Greeting name="async" /></main>))
+		.into_response_async()
+		.await;
+
+	assert_eq!(response.status(), 200);
+
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	assert_eq!(body, "<main><p>Hello, async</p></main>");
+}
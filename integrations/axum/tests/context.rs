@@ -0,0 +1,41 @@
+use axum::{body::to_bytes, extract::FromRequestParts, http::Request, response::IntoResponse};
+use rstml_component::{component, html, ContextStore, HtmlContent};
+use rstml_component_axum::HtmlContext;
+use std::sync::Arc;
+
+struct CurrentUser(&'static str);
+
+#[component(UserName)]
+fn user_name(#[context] user: Option<Arc<CurrentUser>>) -> impl HtmlContent {
+	html!(<span>{user.map_or("guest", |user| user.0)}</span>)
+}
+
+#[tokio::test]
+async fn seeds_context_from_request_extensions() {
+	let (mut parts, ()) = Request::new(()).into_parts();
+	parts
+		.extensions
+		.get_or_insert_default::<ContextStore>()
+		.insert(CurrentUser("alice"));
+
+	let context = HtmlContext::from_request_parts(&mut parts, &())
+		.await
+		.unwrap();
+	let response = context.render(html!(<UserName />)).into_response();
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+	assert_eq!(body, "<span>alice</span>");
+}
+
+#[tokio::test]
+async fn empty_context_without_extension() {
+	let (mut parts, ()) = Request::new(()).into_parts();
+
+	let context = HtmlContext::from_request_parts(&mut parts, &())
+		.await
+		.unwrap();
+	let response = context.render(html!(<UserName />)).into_response();
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+	assert_eq!(body, "<span>guest</span>");
+}
@@ -0,0 +1,51 @@
+use axum::{body::to_bytes, response::IntoResponse};
+use rstml_component::{component, html, HtmlContent, Suspense};
+use rstml_component_axum::StreamingHtml;
+
+#[tokio::test]
+async fn streams_suspended_content() {
+	let response = StreamingHtml(html!(
+		<main>
+			<Suspense fallback={html!("Loading")}>
+				{async {
+					tokio::task::yield_now().await;
+					html!(<p>"Loaded"</p>)
+				}}
+			</Suspense>
+		</main>
+	))
+	.into_response();
+
+	assert_eq!(response.headers()["content-type"], "text/html");
+
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let body = std::str::from_utf8(&body).unwrap();
+
+	assert!(body.starts_with(
+		"<main><rstml-suspense id=\"rstml-suspense-0\">Loading</rstml-suspense></main><template id=\"rstml-suspense-content-0\"><p>Loaded</p></template><script>"
+	));
+}
+
+#[component(Greeting)]
+async fn greeting(name: &'static str) -> impl HtmlContent {
+	tokio::task::yield_now().await;
+	html!(<p>"Hello, "{name}</p>)
+}
+
+#[tokio::test]
+async fn awaits_async_components() {
+	let response = StreamingHtml(html!(
+		<main>
+			<Greeting name="page" />
+			<Suspense>{async { html!(<Greeting name="chunk" />) }}</Suspense>
+		</main>
+	))
+	.into_response();
+
+	let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+	let body = std::str::from_utf8(&body).unwrap();
+
+	assert!(body.starts_with(
+		"<main><p>Hello, page</p><rstml-suspense id=\"rstml-suspense-0\"></rstml-suspense></main><template id=\"rstml-suspense-content-0\"><p>Hello, chunk</p></template><script>"
+	));
+}
@@ -0,0 +1,145 @@
+use convert_case::{Case, Casing};
+use proc_macro2::{Literal, TokenStream};
+use proc_macro2_diagnostics::SpanDiagnosticExt;
+use quote::quote;
+use syn::{spanned::Spanned, Fields, Item, ItemEnum, ItemStruct, LitStr, Member, Variant};
+
+pub fn derive_html_attribute_value(input: TokenStream) -> TokenStream {
+	let full_span = input.span();
+	let result = match syn::parse2(input) {
+		Ok(Item::Enum(item_enum)) => enum_attribute_value(&item_enum),
+		Ok(Item::Struct(item_struct)) => struct_attribute_value(&item_struct),
+		Ok(_) => {
+			return full_span
+				.error("Derived `HtmlAttributeValue`s must be enums or structs")
+				.emit_as_item_tokens()
+		}
+		Err(err) => return err.into_compile_error(),
+	};
+
+	match result {
+		Ok(tokens) => tokens,
+		Err(err) => err.into_compile_error(),
+	}
+}
+
+fn impl_attribute_value(
+	ident: &syn::Ident,
+	generics: &syn::Generics,
+	body: TokenStream,
+) -> TokenStream {
+	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+	quote! {
+		#[automatically_derived]
+		impl #impl_generics ::rstml_component::HtmlAttributeValue for #ident #ty_generics #where_clause {
+			fn fmt(self, formatter: &mut ::rstml_component::HtmlAttributeFormatter) -> ::std::fmt::Result {
+				#body
+			}
+		}
+	}
+}
+
+/// Structs with a single field forward to the value of that field.
+fn struct_attribute_value(input: &ItemStruct) -> syn::Result<TokenStream> {
+	let member = match single_field(&input.fields) {
+		Some(member) => member,
+		None => {
+			return Err(syn::Error::new(
+				input.fields.span(),
+				"Derived `HtmlAttributeValue` structs must have a single field",
+			))
+		}
+	};
+
+	Ok(impl_attribute_value(
+		&input.ident,
+		&input.generics,
+		quote!(::rstml_component::HtmlAttributeValue::fmt(self.#member, formatter)),
+	))
+}
+
+/// Unit variants are written as their kebab-cased name, or the name given by
+/// `#[html(value = "...")]`, and variants with a single field forward to the value of that
+/// field.
+fn enum_attribute_value(input: &ItemEnum) -> syn::Result<TokenStream> {
+	let arms = input
+		.variants
+		.iter()
+		.map(variant_arm)
+		.collect::<syn::Result<Vec<_>>>()?;
+
+	// an empty enum can never be formatted
+	let body = if arms.is_empty() {
+		quote!(match self {})
+	} else {
+		quote! {
+			match self { #(#arms)* }
+			Ok(())
+		}
+	};
+
+	Ok(impl_attribute_value(&input.ident, &input.generics, body))
+}
+
+fn variant_arm(variant: &Variant) -> syn::Result<TokenStream> {
+	let variant_ident = &variant.ident;
+	let value = variant_value(variant)?;
+
+	match (&variant.fields, value) {
+		(Fields::Unit, value) => {
+			let value = value.unwrap_or_else(|| variant_ident.to_string().to_case(Case::Kebab));
+			let value = Literal::byte_string(value.as_bytes());
+			Ok(quote!(Self::#variant_ident => formatter.write(#value),))
+		}
+
+		(fields, None) => match single_field(fields) {
+			Some(member) => Ok(quote! {
+				Self::#variant_ident { #member: value } => {
+					::rstml_component::HtmlAttributeValue::fmt(value, formatter)?
+				}
+			}),
+			None => Err(syn::Error::new(
+				fields.span(),
+				"variants must either be unit variants or have a single field",
+			)),
+		},
+
+		(fields, Some(_)) => Err(syn::Error::new(
+			fields.span(),
+			"`#[html(value = \"...\")]` can only be used on unit variants",
+		)),
+	}
+}
+
+fn variant_value(variant: &Variant) -> syn::Result<Option<String>> {
+	let mut value = None;
+
+	for attr in variant
+		.attrs
+		.iter()
+		.filter(|attr| attr.path().is_ident("html"))
+	{
+		attr.parse_nested_meta(|meta| {
+			if meta.path.is_ident("value") {
+				let lit: LitStr = meta.value()?.parse()?;
+				value = Some(lit.value());
+				Ok(())
+			} else {
+				Err(meta.error("unknown `html` attribute, expected `value`"))
+			}
+		})?;
+	}
+
+	Ok(value)
+}
+
+fn single_field(fields: &Fields) -> Option<Member> {
+	match fields {
+		Fields::Named(fields) if fields.named.len() == 1 => {
+			Some(Member::Named(fields.named[0].ident.clone()?))
+		}
+		Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Some(Member::Unnamed(0.into())),
+		_ => None,
+	}
+}
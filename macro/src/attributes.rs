@@ -0,0 +1,153 @@
+use convert_case::{Case, Casing};
+use proc_macro2::{Literal, TokenStream};
+use proc_macro2_diagnostics::SpanDiagnosticExt;
+use quote::quote;
+use syn::{spanned::Spanned, Fields, Item, ItemStruct, LitStr, Type};
+
+use crate::component::option_inner_type;
+
+pub fn derive_html_attributes(input: TokenStream) -> TokenStream {
+	let full_span = input.span();
+	let input = match syn::parse2(input) {
+		Ok(Item::Struct(item_struct)) => item_struct,
+		Ok(_) => {
+			return full_span
+				.error("Derived `HtmlAttributes` must be structs")
+				.emit_as_item_tokens()
+		}
+		Err(err) => return err.into_compile_error(),
+	};
+
+	match html_attributes(&input) {
+		Ok(tokens) => tokens,
+		Err(err) => err.into_compile_error(),
+	}
+}
+
+fn html_attributes(input: &ItemStruct) -> syn::Result<TokenStream> {
+	if let Fields::Unnamed(fields) = &input.fields {
+		return Err(syn::Error::new(
+			fields.span(),
+			"Derived `HtmlAttributes` cannot have tuple fields",
+		));
+	}
+
+	let ident = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	let writes = input
+		.fields
+		.iter()
+		.map(|field| AttributeField::from_field(field).map(|field| field.write()))
+		.collect::<syn::Result<Vec<_>>>()?;
+
+	Ok(quote! {
+		#[automatically_derived]
+		impl #impl_generics ::rstml_component::HtmlAttributes for #ident #ty_generics #where_clause {
+			fn fmt(self, formatter: &mut ::rstml_component::HtmlAttributesFormatter) -> ::std::fmt::Result {
+				#(#writes)*
+				Ok(())
+			}
+		}
+	})
+}
+
+struct AttributeField<'a> {
+	field: &'a syn::Field,
+	name: String,
+	flatten: bool,
+	skip: bool,
+	as_value: bool,
+}
+
+impl<'a> AttributeField<'a> {
+	fn from_field(field: &'a syn::Field) -> syn::Result<Self> {
+		let ident = field.ident.as_ref().expect("named field");
+		let mut result = Self {
+			field,
+			name: ident
+				.to_string()
+				.trim_start_matches("r#")
+				.to_case(Case::Kebab),
+			flatten: false,
+			skip: false,
+			as_value: false,
+		};
+
+		for attr in field
+			.attrs
+			.iter()
+			.filter(|attr| attr.path().is_ident("html"))
+		{
+			attr.parse_nested_meta(|meta| {
+				if meta.path.is_ident("rename") {
+					let name: LitStr = meta.value()?.parse()?;
+					result.name = name.value();
+					Ok(())
+				} else if meta.path.is_ident("flatten") {
+					result.flatten = true;
+					Ok(())
+				} else if meta.path.is_ident("skip") {
+					result.skip = true;
+					Ok(())
+				} else if meta.path.is_ident("as_value") {
+					result.as_value = true;
+					Ok(())
+				} else {
+					Err(
+						meta.error(
+							"unknown `html` attribute, expected `rename`, `flatten`, `skip` or `as_value`",
+						),
+					)
+				}
+			})?;
+		}
+
+		Ok(result)
+	}
+
+	/// Generates the code writing the field. `Option` fields are only written when they are
+	/// `Some`, and `bool` fields are written as boolean attributes (present only when `true`),
+	/// unless `as_value` is set.
+	fn write(&self) -> TokenStream {
+		let ident = &self.field.ident;
+		if self.skip {
+			return TokenStream::new();
+		}
+
+		if self.flatten {
+			return quote! {
+				::rstml_component::HtmlAttributes::fmt(self.#ident, formatter)?;
+			};
+		}
+
+		let name = Literal::byte_string(self.name.as_bytes());
+		let write = |value: TokenStream, ty: &Type| {
+			if is_bool(ty) && !self.as_value {
+				quote! {
+					if #value {
+						formatter.write_attribute(#name, ())?;
+					}
+				}
+			} else {
+				quote!(formatter.write_attribute(#name, #value)?;)
+			}
+		};
+
+		match option_inner_type(&self.field.ty) {
+			Some(ty) => {
+				let write = write(quote!(value), ty);
+				quote! {
+					if let ::core::option::Option::Some(value) = self.#ident {
+						#write
+					}
+				}
+			}
+			None => write(quote!(self.#ident), &self.field.ty),
+		}
+	}
+}
+
+fn is_bool(ty: &Type) -> bool {
+	matches!(ty, Type::Path(path) if path.qself.is_none() && path.path.is_ident("bool"))
+}
@@ -1,28 +1,55 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use proc_macro2_diagnostics::SpanDiagnosticExt;
-use quote::quote;
-use syn::{spanned::Spanned, Fields, Item};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+	parse_quote, spanned::Spanned, Expr, GenericArgument, GenericParam, Generics, Ident, Item,
+	ItemEnum, ItemStruct, Member, PathArguments, Token, Type,
+};
 
 pub fn derive_html_component(input: TokenStream) -> TokenStream {
 	let full_span = input.span();
 	let input = match syn::parse2(input) {
 		Ok(Item::Struct(item_struct)) => item_struct,
+		Ok(Item::Enum(item_enum)) => return enum_component(&item_enum),
 		Ok(_) => {
 			return full_span
-				.error("Derived `HtmlComponent`s must be structs")
+				.error("Derived `HtmlComponent`s must be structs or enums")
 				.emit_as_item_tokens()
 		}
 		Err(err) => return err.into_compile_error(),
 	};
 
-	if matches!(&input.fields, Fields::Unnamed(_)) {
-		return input
-			.fields
-			.span()
-			.error("Derived `HtmlComponent`s cannot have tuple fields")
-			.emit_as_item_tokens();
+	let fields = match input
+		.fields
+		.iter()
+		.enumerate()
+		.map(|(idx, field)| ComponentField::from_field(idx, field, input.fields.len()))
+		.collect::<syn::Result<Vec<_>>>()
+	{
+		Ok(fields) => fields,
+		Err(err) => return err.into_compile_error(),
+	};
+
+	let ident = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	let builder = builder(&input, &fields);
+
+	quote! {
+		#[automatically_derived]
+		impl #impl_generics ::rstml_component::HtmlComponent for #ident #ty_generics #where_clause {
+			type Content = Self;
+
+			fn into_content(self) -> Self::Content {
+				self
+			}
+		}
+
+		#builder
 	}
+}
 
+/// Enums are used as content rather than constructed from props, so they don't get a builder.
+fn enum_component(input: &ItemEnum) -> TokenStream {
 	let ident = &input.ident;
 	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
@@ -37,3 +64,376 @@ pub fn derive_html_component(input: TokenStream) -> TokenStream {
 		}
 	}
 }
+
+enum FieldDefault {
+	/// The field is required.
+	None,
+
+	/// The field defaults to `Default::default()`.
+	Default,
+
+	/// The field defaults to the given expression.
+	Expr(Box<Expr>),
+}
+
+struct ComponentField<'a> {
+	/// The name of the prop.
+	ident: Ident,
+	/// The struct member the prop is assigned to.
+	member: Member,
+	ty: &'a Type,
+	default: FieldDefault,
+	into: bool,
+	strip_option: bool,
+}
+
+impl<'a> ComponentField<'a> {
+	/// Tuple struct fields are exposed as the `value` prop for newtypes, and as `_0`, `_1`, ...
+	/// otherwise.
+	fn from_field(idx: usize, field: &'a syn::Field, field_count: usize) -> syn::Result<Self> {
+		let (ident, member) = match &field.ident {
+			Some(ident) => (ident.clone(), Member::Named(ident.clone())),
+			None if field_count == 1 => (
+				Ident::new("value", field.span()),
+				Member::Unnamed(idx.into()),
+			),
+			None => (
+				format_ident!("_{}", idx, span = field.span()),
+				Member::Unnamed(idx.into()),
+			),
+		};
+
+		if ident == "build" {
+			return Err(syn::Error::new(
+				ident.span(),
+				"`build` cannot be used as a prop name, as the builder of the component uses it to build the component",
+			));
+		}
+
+		let mut result = Self {
+			ident,
+			member,
+			ty: &field.ty,
+			default: FieldDefault::None,
+			into: false,
+			strip_option: false,
+		};
+
+		for attr in field
+			.attrs
+			.iter()
+			.filter(|attr| attr.path().is_ident("html"))
+		{
+			attr.parse_nested_meta(|meta| {
+				if meta.path.is_ident("default") {
+					result.default = if meta.input.peek(Token![=]) {
+						FieldDefault::Expr(Box::new(meta.value()?.parse()?))
+					} else {
+						FieldDefault::Default
+					};
+
+					Ok(())
+				} else if meta.path.is_ident("optional") {
+					result.default = FieldDefault::Default;
+					result.strip_option = option_inner_type(&field.ty).is_some();
+					Ok(())
+				} else if meta.path.is_ident("into") {
+					result.into = true;
+					Ok(())
+				} else {
+					Err(meta.error("unknown `html` attribute, expected `default`, `optional` or `into`"))
+				}
+			})?;
+		}
+
+		Ok(result)
+	}
+
+	fn is_required(&self) -> bool {
+		matches!(self.default, FieldDefault::None)
+	}
+}
+
+/// Returns `T` if the type is `Option<T>`.
+pub fn option_inner_type(ty: &Type) -> Option<&Type> {
+	let Type::Path(path) = ty else {
+		return None;
+	};
+
+	let segment = path.path.segments.last()?;
+	if segment.ident != "Option" {
+		return None;
+	}
+
+	match &segment.arguments {
+		PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+			GenericArgument::Type(ty) => Some(ty),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+/// Generates a typed builder for the component, to construct it from plain Rust code or from
+/// templates whose props end with `..`. Each prop is tracked in the builder's type as either
+/// `()` (unset) or `(T,)` (set), so that missing required props are reported at compile time.
+fn builder(input: &ItemStruct, fields: &[ComponentField]) -> TokenStream {
+	let ItemStruct {
+		vis,
+		ident,
+		generics,
+		..
+	} = input;
+	let builder_ident = format_ident!("{}Builder", ident);
+	let props_ident = Ident::new("__Props", Span::call_site());
+
+	// the builder has the generics of the component, plus a type parameter for the props
+	let mut component_generics = generics.clone();
+	for param in component_generics.params.iter_mut() {
+		if let GenericParam::Type(param) = param {
+			param.eq_token = None;
+			param.default = None;
+		}
+	}
+
+	let (_, ty_generics, _) = generics.split_for_impl();
+	let ty_generics = quote!(#ty_generics);
+	let builder_args = |props: TokenStream| -> TokenStream {
+		let mut args = component_generics
+			.params
+			.iter()
+			.map(|param| match param {
+				GenericParam::Lifetime(param) => {
+					let lifetime = &param.lifetime;
+					quote!(#lifetime)
+				}
+				GenericParam::Type(param) => {
+					let ident = &param.ident;
+					quote!(#ident)
+				}
+				GenericParam::Const(param) => {
+					let ident = &param.ident;
+					quote!(#ident)
+				}
+			})
+			.collect::<Vec<_>>();
+
+		args.push(props);
+		quote!(<#(#args),*>)
+	};
+
+	let with_props = |props: &[Ident]| -> Generics {
+		let mut generics = component_generics.clone();
+		generics.params.extend(
+			props
+				.iter()
+				.map(|ident| -> GenericParam { parse_quote!(#ident) }),
+		);
+		generics
+	};
+
+	let slots = (0..fields.len())
+		.map(|idx| format_ident!("__P{}", idx))
+		.collect::<Vec<_>>();
+	let values = fields
+		.iter()
+		.map(|field| format_ident!("__{}", field.ident.to_string().trim_start_matches("r#")))
+		.collect::<Vec<_>>();
+
+	let builder_generics = with_props(std::slice::from_ref(&props_ident));
+	let (builder_impl_generics, _, builder_where_clause) = builder_generics.split_for_impl();
+
+	let unset = fields.iter().map(|_| quote!(())).collect::<Vec<_>>();
+	let empty_props = builder_args(quote!((#(#unset,)*)));
+	let (component_impl_generics, _, component_where_clause) = component_generics.split_for_impl();
+
+	let setters = fields.iter().enumerate().map(|(idx, field)| {
+		let other_slots = slots
+			.iter()
+			.enumerate()
+			.filter(|(i, _)| *i != idx)
+			.map(|(_, slot)| slot.clone())
+			.collect::<Vec<_>>();
+		let setter_generics = with_props(&other_slots);
+		let (setter_impl_generics, _, setter_where_clause) = setter_generics.split_for_impl();
+
+		let slot_types = |current: TokenStream| {
+			let slots = slots.iter().enumerate().map(|(i, slot)| {
+				if i == idx {
+					current.clone()
+				} else {
+					quote!(#slot)
+				}
+			});
+			quote!((#(#slots,)*))
+		};
+
+		let field_ident = &field.ident;
+		let ty = field.ty;
+		let doc = format!("Sets the `{}` prop.", field_ident);
+		let from_args = builder_args(slot_types(quote!(())));
+		let to_args = builder_args(slot_types(quote!((#ty,))));
+		let destructure =
+			values.iter().enumerate().map(
+				|(i, value)| {
+					if i == idx {
+						quote!(())
+					} else {
+						quote!(#value)
+					}
+				},
+			);
+		let restructure = values.iter().enumerate().map(|(i, value)| {
+			if i == idx {
+				quote!((value,))
+			} else {
+				quote!(#value)
+			}
+		});
+
+		let (param_ty, convert) = if field.into || field.strip_option {
+			(
+				quote!(impl ::core::convert::Into<#ty>),
+				quote!(value.into()),
+			)
+		} else {
+			(quote!(#ty), quote!(value))
+		};
+
+		quote! {
+			#[automatically_derived]
+			impl #setter_impl_generics #builder_ident #from_args #setter_where_clause {
+				#[doc = #doc]
+				#[allow(non_snake_case)]
+				#vis fn #field_ident(self, value: #param_ty) -> #builder_ident #to_args {
+					let value = #convert;
+					let (#(#destructure,)*) = self.props;
+					#builder_ident {
+						props: (#(#restructure,)*),
+						_component: ::core::marker::PhantomData,
+					}
+				}
+			}
+		}
+	});
+
+	let required_traits = fields
+		.iter()
+		.filter(|field| field.is_required())
+		.map(|field| {
+			let trait_ident = required_trait_ident(&field.ident);
+			let message = format!(
+				"missing required prop `{}` for component `{}`",
+				field.ident, ident
+			);
+			let label = format!("the `{}` prop is required", field.ident);
+
+			quote! {
+				#[diagnostic::on_unimplemented(message = #message, label = #label)]
+				pub trait #trait_ident<T> {
+					fn into_prop(self) -> T;
+				}
+
+				impl<T> #trait_ident<T> for (T,) {
+					fn into_prop(self) -> T {
+						self.0
+					}
+				}
+			}
+		});
+
+	// the prop bounds are placed on the method rather than the impl, so that a missing prop
+	// is reported through the `on_unimplemented` message instead of a missing method
+	let build_generics = with_props(&slots);
+	let (build_impl_generics, _, build_where_clause) = build_generics.split_for_impl();
+	let build_bounds = fields.iter().zip(&slots).map(|(field, slot)| {
+		let ty = field.ty;
+		if field.is_required() {
+			let trait_ident = required_trait_ident(&field.ident);
+			quote!(#slot: #trait_ident<#ty>)
+		} else {
+			quote!(#slot: OptionalProp<#ty>)
+		}
+	});
+	let build_args = builder_args(quote!((#(#slots,)*)));
+	let build_fields = fields.iter().zip(&values).map(|(field, value)| {
+		let field_ident = &field.member;
+		match &field.default {
+			FieldDefault::None => quote!(#field_ident: #value.into_prop()),
+			FieldDefault::Default => {
+				quote!(#field_ident: #value.into_prop_or_else(::core::default::Default::default))
+			}
+			FieldDefault::Expr(expr) => {
+				let expr = &**expr;
+				quote_spanned!(expr.span()=> #field_ident: #value.into_prop_or_else(|| #expr))
+			}
+		}
+	});
+
+	let builder_doc = format!("Builder for the [`{}`] component.", ident);
+	let builder_fn_doc = format!(
+		"Creates a builder for the [`{}`] component, to construct it from plain Rust code.",
+		ident
+	);
+
+	quote! {
+		#[doc = #builder_doc]
+		#[must_use]
+		#vis struct #builder_ident #builder_impl_generics #builder_where_clause {
+			props: #props_ident,
+			_component: ::core::marker::PhantomData<fn() -> #ident #ty_generics>,
+		}
+
+		#[allow(non_camel_case_types)]
+		const _: () = {
+			pub trait OptionalProp<T> {
+				fn into_prop_or_else(self, default: impl FnOnce() -> T) -> T;
+			}
+
+			impl<T> OptionalProp<T> for () {
+				fn into_prop_or_else(self, default: impl FnOnce() -> T) -> T {
+					default()
+				}
+			}
+
+			impl<T> OptionalProp<T> for (T,) {
+				fn into_prop_or_else(self, _default: impl FnOnce() -> T) -> T {
+					self.0
+				}
+			}
+
+			#(#required_traits)*
+
+			#[automatically_derived]
+			impl #component_impl_generics #ident #ty_generics #component_where_clause {
+				#[doc = #builder_fn_doc]
+				#vis fn builder() -> #builder_ident #empty_props {
+					#builder_ident {
+						props: (#(#unset,)*),
+						_component: ::core::marker::PhantomData,
+					}
+				}
+			}
+
+			#(#setters)*
+
+			#[automatically_derived]
+			impl #build_impl_generics #builder_ident #build_args #build_where_clause {
+				/// Builds the component from the props that have been set.
+				#vis fn build(self) -> #ident #ty_generics
+				where
+					#(#build_bounds,)*
+				{
+					let (#(#values,)*) = self.props;
+					#ident {
+						#(#build_fields,)*
+					}
+				}
+			}
+		};
+	}
+}
+
+fn required_trait_ident(field: &Ident) -> Ident {
+	format_ident!("__Required_{}", field.to_string().trim_start_matches("r#"))
+}
@@ -0,0 +1,122 @@
+use proc_macro2::{Delimiter, Group, Span, TokenStream};
+use proc_macro2_diagnostics::SpanDiagnosticExt;
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, Fields, Ident, Item, ItemEnum, Variant};
+
+use crate::template::Template;
+
+pub fn derive_html_content(input: TokenStream) -> TokenStream {
+	let full_span = input.span();
+	let input = match syn::parse2(input) {
+		Ok(Item::Enum(item_enum)) => item_enum,
+		Ok(_) => {
+			return full_span
+				.error("Derived `HtmlContent`s must be enums")
+				.emit_as_item_tokens()
+		}
+		Err(err) => return err.into_compile_error(),
+	};
+
+	match html_content(&input) {
+		Ok(tokens) => tokens,
+		Err(err) => err.into_compile_error(),
+	}
+}
+
+fn html_content(input: &ItemEnum) -> syn::Result<TokenStream> {
+	let ident = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	let formatter = Ident::new("__html", Span::call_site());
+
+	let arms = input
+		.variants
+		.iter()
+		.map(|variant| variant_arm(variant, &formatter))
+		.collect::<syn::Result<Vec<_>>>()?;
+
+	// an empty enum can never be formatted
+	let body = if arms.is_empty() {
+		quote!(match self {})
+	} else {
+		quote!(match self { #(#arms)* })
+	};
+
+	Ok(quote! {
+		#[automatically_derived]
+		impl #impl_generics ::rstml_component::HtmlContent for #ident #ty_generics #where_clause {
+			fn fmt(self, #formatter: &mut ::rstml_component::HtmlFormatter) -> ::std::fmt::Result {
+				#body
+			}
+		}
+	})
+}
+
+/// Generates the match arm for a variant. A variant is either formatted using the template
+/// given by `#[html(template = { ... })]`, with its fields in scope, or by delegating to its
+/// only field.
+fn variant_arm(variant: &Variant, formatter: &Ident) -> syn::Result<TokenStream> {
+	let variant_ident = &variant.ident;
+	let bindings = variant
+		.fields
+		.iter()
+		.enumerate()
+		.map(|(idx, field)| match &field.ident {
+			Some(ident) => ident.clone(),
+			None => format_ident!("_{}", idx),
+		})
+		.collect::<Vec<_>>();
+
+	let pattern = match &variant.fields {
+		Fields::Named(_) => quote!(Self::#variant_ident { #(#bindings),* }),
+		Fields::Unnamed(_) => quote!(Self::#variant_ident(#(#bindings),*)),
+		Fields::Unit => quote!(Self::#variant_ident),
+	};
+
+	if let Some(template) = variant_template(variant)? {
+		let template = template.with_formatter(formatter);
+		return Ok(quote! {
+			#[allow(unused_variables)]
+			#pattern => {
+				#template
+				Ok(())
+			}
+		});
+	}
+
+	match &*bindings {
+		[binding] => Ok(quote!(#pattern => #formatter.write_content(#binding),)),
+		_ => Err(syn::Error::new(
+			variant.span(),
+			"variants must either have a single field, or a template given by `#[html(template = { ... })]`",
+		)),
+	}
+}
+
+fn variant_template(variant: &Variant) -> syn::Result<Option<Template>> {
+	let mut template = None;
+
+	for attr in variant
+		.attrs
+		.iter()
+		.filter(|attr| attr.path().is_ident("html"))
+	{
+		attr.parse_nested_meta(|meta| {
+			if meta.path.is_ident("template") {
+				let group: Group = meta.value()?.parse()?;
+				if group.delimiter() != Delimiter::Brace {
+					return Err(syn::Error::new(
+						group.span(),
+						"expected a template in braces, such as `template = { <p>\"Hello\"</p> }`",
+					));
+				}
+
+				template = Some(Template::parser().parse_token_stream(group.stream()));
+				Ok(())
+			} else {
+				Err(meta.error("unknown `html` attribute, expected `template`"))
+			}
+		})?;
+	}
+
+	Ok(template)
+}
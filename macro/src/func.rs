@@ -1,10 +1,10 @@
 use proc_macro2::{Span, TokenStream};
 use proc_macro2_diagnostics::{Diagnostic, Level};
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
-	parse::Parse, punctuated::Punctuated, spanned::Spanned, token::Comma, Field, FnArg, GenericParam,
-	Generics, Ident, Pat, Path, PathArguments, PathSegment, Token, Type, TypeImplTrait, TypeParam,
-	TypePath, Visibility,
+	parse::Parse, parse_quote, parse_quote_spanned, punctuated::Punctuated, spanned::Spanned,
+	token::Comma, Attribute, Field, FnArg, GenericParam, Generics, Ident, Meta, Pat, Path,
+	PathArguments, PathSegment, Token, Type, TypeImplTrait, TypeParam, TypePath, Visibility,
 };
 
 trait IdentPath {
@@ -54,6 +54,12 @@ where
 	}
 }
 
+/// An argument of a component function, which is either a prop or read from the context.
+enum ComponentArg {
+	Prop(Ident),
+	Context(Ident, Box<Type>),
+}
+
 struct ComponentAttrs {
 	vis: syn::Visibility,
 	name: syn::Ident,
@@ -153,11 +159,11 @@ impl ComponentStructBuilder {
 		}
 	}
 
-	fn push_field(&mut self, ident: Ident, ty: Type) {
+	fn push_field(&mut self, ident: Ident, ty: Type, attrs: Vec<Attribute>) {
 		let ty = self.resolve_type(&ty, &IdentParts(ident.to_string().split('_')));
 
 		let field = Field {
-			attrs: vec![],
+			attrs,
 			vis: Visibility::Public(Token![pub](Span::call_site())),
 			mutability: syn::FieldMutability::None,
 			ident: Some(ident),
@@ -238,7 +244,7 @@ pub fn component(attr: TokenStream, input: TokenStream) -> TokenStream {
 	let mut diagnostics: Vec<Diagnostic> = vec![];
 
 	// parse input
-	let input: syn::ItemFn = match syn::parse2(input) {
+	let mut input: syn::ItemFn = match syn::parse2(input) {
 		Ok(input) => input,
 		Err(err) => return err.to_compile_error(),
 	};
@@ -255,12 +261,6 @@ pub fn component(attr: TokenStream, input: TokenStream) -> TokenStream {
 			Level::Error,
 			"component function must not be const",
 		))
-	} else if let Some(asyncness) = input.sig.asyncness {
-		diagnostics.push(Diagnostic::spanned(
-			asyncness.span(),
-			Level::Error,
-			"component function must not be async",
-		));
 	} else if let Some(unsafety) = input.sig.unsafety {
 		diagnostics.push(Diagnostic::spanned(
 			unsafety.span(),
@@ -276,8 +276,53 @@ pub fn component(attr: TokenStream, input: TokenStream) -> TokenStream {
 	}
 
 	let mut struct_builder = ComponentStructBuilder::new(attr, input.sig.generics.clone());
+	let mut component_args = Vec::new();
+
+	for arg in input.sig.inputs.iter_mut() {
+		// `#[prop(...)]` attributes are moved to the generated struct's fields as `#[html(...)]`
+		let mut field_attrs = Vec::new();
+		let mut context = None;
+		if let FnArg::Typed(pat_type) = arg {
+			let (props, attrs) = std::mem::take(&mut pat_type.attrs)
+				.into_iter()
+				.partition::<Vec<_>, _>(|attr| attr.path().is_ident("prop"));
+			let (contexts, attrs) = attrs
+				.into_iter()
+				.partition::<Vec<_>, _>(|attr| attr.path().is_ident("context"));
+			pat_type.attrs = attrs;
+
+			// `#[context]` arguments are read from the formatter instead of being props
+			for attr in contexts {
+				match &attr.meta {
+					Meta::Path(_) if !props.is_empty() => diagnostics.push(Diagnostic::spanned(
+						attr.span(),
+						Level::Error,
+						"`#[context]` arguments can't have `#[prop(...)]` attributes",
+					)),
+					Meta::Path(_) => context = Some(attr),
+					_ => diagnostics.push(Diagnostic::spanned(
+						attr.span(),
+						Level::Error,
+						"expected `#[context]`",
+					)),
+				}
+			}
+
+			for prop in props {
+				match &prop.meta {
+					Meta::List(list) => {
+						let tokens = &list.tokens;
+						field_attrs.push(parse_quote_spanned!(prop.span()=> #[html(#tokens)]));
+					}
+					_ => diagnostics.push(Diagnostic::spanned(
+						prop.span(),
+						Level::Error,
+						"expected `#[prop(...)]`",
+					)),
+				}
+			}
+		}
 
-	for arg in input.sig.inputs.iter() {
 		match arg {
 			FnArg::Receiver(_) => {
 				diagnostics.push(Diagnostic::spanned(
@@ -292,7 +337,14 @@ pub fn component(attr: TokenStream, input: TokenStream) -> TokenStream {
 				let ty = *pat_type.ty.clone();
 
 				match pat {
-					Pat::Ident(pat) => struct_builder.push_field(pat.ident, ty),
+					Pat::Ident(pat) if context.is_some() => {
+						component_args.push(ComponentArg::Context(pat.ident, Box::new(ty)));
+					}
+
+					Pat::Ident(pat) => {
+						component_args.push(ComponentArg::Prop(pat.ident.clone()));
+						struct_builder.push_field(pat.ident, ty, field_attrs);
+					}
 
 					Pat::TupleStruct(pat) => {
 						diagnostics.push(Diagnostic::spanned(
@@ -338,22 +390,70 @@ pub fn component(attr: TokenStream, input: TokenStream) -> TokenStream {
 		}
 	}
 
-	let (generated_struct, ident, generics, fields) = struct_builder.build();
+	let (generated_struct, ident, generics, _) = struct_builder.build();
 
 	let input_ident = input.sig.ident.clone();
 	let mut fn_args = Vec::new();
-	for field in fields.iter() {
-		// unwrap shouldn't panic since each field is generated with an ident
-		let ident = field.ident.clone().unwrap();
-		fn_args.push(quote!(self.#ident,));
+	let mut context_args = Vec::new();
+	for arg in component_args {
+		match arg {
+			ComponentArg::Prop(ident) => fn_args.push(quote!(self.#ident,)),
+			ComponentArg::Context(ident, ty) => {
+				let context_ident = format_ident!("__context_{}", ident);
+				context_args.push(quote! {
+					let #context_ident = <#ty as ::rstml_component::FromContext>::from_context(formatter)?;
+				});
+				fn_args.push(quote!(#context_ident,));
+			}
+		}
 	}
 
 	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-	let impl_block = quote! {
-		impl #impl_generics ::rstml_component::HtmlContent for #ident #ty_generics #where_clause {
-			fn fmt(self, formatter: &mut ::rstml_component::HtmlFormatter) -> std::fmt::Result {
-				formatter.write_content(#input_ident (#(#fn_args)*))
+	let impl_block = if input.sig.asyncness.is_some() && !context_args.is_empty() {
+		// the context of async components with context arguments is read when they are written,
+		// so they don't implement `AsyncHtmlContent`
+		quote! {
+			impl #impl_generics ::rstml_component::HtmlContent for #ident #ty_generics #where_clause {
+				fn fmt(self, formatter: &mut ::rstml_component::HtmlFormatter) -> std::fmt::Result {
+					#(#context_args)*
+					formatter.write_async_content(#input_ident (#(#fn_args)*))
+				}
+			}
+		}
+	} else if input.sig.asyncness.is_some() {
+		// async components are resolved by the `AsyncRender` that renders the template they are
+		// used in, so they are written as async content
+		let mut content_generics = generics.clone();
+		content_generics
+			.make_where_clause()
+			.predicates
+			.push(parse_quote!(Self: 'static));
+		let content_where_clause = &content_generics.where_clause;
+
+		quote! {
+			impl #impl_generics ::rstml_component::AsyncHtmlContent for #ident #ty_generics #where_clause {
+				fn resolve(
+					self,
+				) -> impl ::std::future::Future<Output = impl ::rstml_component::HtmlContent + ::std::marker::Send>
+							 + ::std::marker::Send {
+					#input_ident (#(#fn_args)*)
+				}
+			}
+
+			impl #impl_generics ::rstml_component::HtmlContent for #ident #ty_generics #content_where_clause {
+				fn fmt(self, formatter: &mut ::rstml_component::HtmlFormatter) -> std::fmt::Result {
+					formatter.write_async_content(self)
+				}
+			}
+		}
+	} else {
+		quote! {
+			impl #impl_generics ::rstml_component::HtmlContent for #ident #ty_generics #where_clause {
+				fn fmt(self, formatter: &mut ::rstml_component::HtmlFormatter) -> std::fmt::Result {
+					#(#context_args)*
+					formatter.write_content(#input_ident (#(#fn_args)*))
+				}
 			}
 		}
 	};
@@ -1,6 +1,9 @@
+mod attribute_value;
+mod attributes;
 mod component;
-mod template;
+mod content;
 mod func;
+mod template;
 mod write;
 
 #[proc_macro]
@@ -8,16 +11,195 @@ pub fn html(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 	write::html(input.into(), true).into()
 }
 
+/// Like `html!`, but creates a closure that borrows the values it captures instead of moving
+/// them. When the template doesn't move out of its captures, the closure implements `Fn` and
+/// `HtmlRender`, so it can be rendered any number of times.
+#[proc_macro]
+pub fn html_ref(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	write::html(input.into(), false).into()
+}
+
+/// Expands a template without any dynamic values or components to a `StaticHtml` constant,
+/// which can be used in `const` and `static` items.
+///
+/// ```
+/// # use rstml_component::{static_html, StaticHtml};
+/// const FOOTER: StaticHtml = static_html!(<footer>"© 2024"</footer>);
+/// assert_eq!(FOOTER.as_str(), "<footer>© 2024</footer>");
+/// ```
+#[proc_macro]
+pub fn static_html(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	write::static_html(input.into()).into()
+}
+
 #[proc_macro]
 pub fn write_html(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 	write::write_html(input.into()).into()
 }
 
+/// Derives `HtmlComponent` for a struct, along with a typed builder to construct the component
+/// from plain Rust code, through `Component::builder()`. Props that are required must be set
+/// before calling `build`, which is checked at compile time.
+///
+/// Templates construct components with a struct literal, which requires every field. When the
+/// props of a component end with `..`, as in `<Page title="Home" .. />`, the template uses the
+/// builder instead, so optional props can be left out and `into` props are converted.
+///
+/// With the builder, the field of a tuple struct with a single field is set through the `value`
+/// prop, such as `<UserName value="alice" .. />`. The fields of other tuple structs are set
+/// through `_0`, `_1`, ...
+///
+/// # Field attributes
+///
+/// - `#[html(default)]` makes the prop optional, defaulting to `Default::default()`.
+/// - `#[html(default = expr)]` makes the prop optional, defaulting to `expr`.
+/// - `#[html(optional)]` makes the prop optional, and for `Option<T>` props allows passing a `T`.
+/// - `#[html(into)]` accepts any value that implements `Into` of the field type.
+///
+/// ```
+/// # use rstml_component::{html, HtmlComponent, HtmlContent, HtmlFormatter};
+/// #[derive(HtmlComponent)]
+/// struct Page {
+///   #[html(into)]
+///   title: String,
+///   #[html(default = "en")]
+///   lang: &'static str,
+///   #[html(optional)]
+///   description: Option<String>,
+/// }
+///
+/// # impl HtmlContent for Page {
+/// #   fn fmt(self, formatter: &mut HtmlFormatter) -> std::fmt::Result {
+/// #     formatter.write_content(self.title)
+/// #   }
+/// # }
+/// let page = Page::builder().title("Home").build();
+/// assert_eq!(page.lang, "en");
+/// assert_eq!(page.description, None);
+/// ```
 #[proc_macro_derive(HtmlComponent, attributes(html))]
 pub fn derive_html_component(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 	component::derive_html_component(input.into()).into()
 }
 
+/// Derives `HtmlContent` for an enum, formatting each variant with its own template.
+///
+/// A variant is formatted using the template given by `#[html(template = { ... })]`, in which
+/// the fields of the variant are in scope (tuple fields are named `_0`, `_1`, ...). Variants
+/// without a template must have a single field, which is formatted as content.
+///
+/// ```
+/// # use rstml_component::{HtmlComponent, HtmlContent};
+/// #[derive(HtmlComponent, HtmlContent)]
+/// enum Alert {
+///   #[html(template = { <div class="alert info">{message}</div> })]
+///   Info { message: String },
+///   #[html(template = { <div class="alert error"><strong>{_0}</strong>{_1}</div> })]
+///   Error(&'static str, String),
+///   Custom(String),
+/// }
+///
+/// let html = Alert::Error("Oops", "Something went wrong".into()).into_string().unwrap();
+/// assert_eq!(html, r#"<div class="alert error"><strong>Oops</strong>Something went wrong</div>"#);
+/// ```
+#[proc_macro_derive(HtmlContent, attributes(html))]
+pub fn derive_html_content(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	content::derive_html_content(input.into()).into()
+}
+
+/// Derives `HtmlAttributes` for a struct, writing each field as an attribute.
+///
+/// Field names are converted to kebab-case, so `aria_label` is written as `aria-label`. `Option`
+/// fields are only written when they are `Some`, and `bool` fields are written as boolean
+/// attributes, which are only present when `true`.
+///
+/// # Field attributes
+///
+/// - `#[html(rename = "name")]` writes the field using the given attribute name.
+/// - `#[html(flatten)]` writes the attributes of a field that implements `HtmlAttributes`.
+/// - `#[html(skip)]` doesn't write the field.
+/// - `#[html(as_value)]` writes a `bool` field as `true` or `false`, such as for `aria-expanded`.
+///
+/// ```
+/// # use rstml_component::{html, HtmlAttributes, HtmlContent};
+/// #[derive(HtmlAttributes)]
+/// struct Htmx {
+///   hx_get: &'static str,
+///   hx_target: Option<&'static str>,
+/// }
+///
+/// #[derive(HtmlAttributes)]
+/// struct ButtonAttributes {
+///   #[html(rename = "type")]
+///   kind: &'static str,
+///   disabled: bool,
+///   #[html(as_value)]
+///   aria_expanded: bool,
+///   #[html(flatten)]
+///   htmx: Htmx,
+/// }
+///
+/// let attrs = ButtonAttributes {
+///   kind: "button",
+///   disabled: false,
+///   aria_expanded: false,
+///   htmx: Htmx { hx_get: "/more", hx_target: None },
+/// };
+///
+/// let html = html! { <button {attrs}>"More"</button> }.into_string().unwrap();
+/// assert_eq!(html, r#"<button type="button" aria-expanded="false" hx-get="/more">More</button>"#);
+/// ```
+#[proc_macro_derive(HtmlAttributes, attributes(html))]
+pub fn derive_html_attributes(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	attributes::derive_html_attributes(input.into()).into()
+}
+
+/// Derives `HtmlAttributeValue` for an enum or a struct with a single field.
+///
+/// Unit variants are written as their name converted to kebab-case, or as the value given by
+/// `#[html(value = "...")]`. Variants and structs with a single field are written as the value
+/// of that field.
+///
+/// ```
+/// # use rstml_component::{html, HtmlAttributeValue, HtmlContent};
+/// #[derive(HtmlAttributeValue)]
+/// enum Target {
+///   #[html(value = "_blank")]
+///   Blank,
+///   #[html(value = "_self")]
+///   Current,
+///   Frame(&'static str),
+/// }
+///
+/// #[derive(HtmlAttributeValue)]
+/// enum Rel {
+///   #[html(value = "noopener")]
+///   NoOpener,
+///   #[html(value = "noreferrer")]
+///   NoReferrer,
+/// }
+///
+/// #[derive(HtmlAttributeValue)]
+/// enum ReferrerPolicy {
+///   NoReferrer,
+///   StrictOriginWhenCrossOrigin,
+/// }
+///
+/// let html = html! {
+///   <a target=Target::Blank rel=Rel::NoOpener referrerpolicy=ReferrerPolicy::NoReferrer>"Link"</a>
+/// }
+/// .into_string()
+/// .unwrap();
+/// assert_eq!(
+///   html,
+///   r#"<a target="_blank" rel="noopener" referrerpolicy="no-referrer">Link</a>"#
+/// );
+/// ```
+#[proc_macro_derive(HtmlAttributeValue, attributes(html))]
+pub fn derive_html_attribute_value(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	attribute_value::derive_html_attribute_value(input.into()).into()
+}
+
 /// Turns a function into a component function, arguments to the attribute macro are the visibility
 /// for the generated struct and the name of the struct. The function should return `impl HtmlContent`.
 ///
@@ -31,6 +213,56 @@ pub fn derive_html_component(input: proc_macro::TokenStream) -> proc_macro::Toke
 ///     <div>{title.into()}</div>
 ///   }
 /// }
+/// ```
+///
+/// # Props
+///
+/// Arguments can be annotated with `#[prop(...)]` to change how they are passed when the props
+/// of the component end with `..` in a template, which constructs the component through its
+/// builder rather than a struct literal:
+///
+/// - `#[prop(default)]` makes the prop optional, defaulting to `Default::default()`.
+/// - `#[prop(default = expr)]` makes the prop optional, defaulting to `expr`.
+/// - `#[prop(optional)]` makes the prop optional, and for `Option<T>` props allows passing a `T`.
+/// - `#[prop(into)]` accepts any value that implements `Into` of the argument type.
+///
+/// Arguments annotated with `#[context]` aren't props, but are read from the context values of
+/// the formatter (see `HtmlFormatter::provide`). An `Arc<T>` argument requires a value of type
+/// `T`, and an `Option<Arc<T>>` argument is `None` when there is none.
+///
+/// ```
+/// # use rstml_component::{html, component, HtmlContent};
+/// #[component(pub Greeting)]
+/// fn greeting(
+///   #[prop(into)] name: String,
+///   #[prop(default = "Hello")] greeting: &'static str,
+///   #[prop(optional)] title: Option<&'static str>,
+/// ) -> impl HtmlContent {
+///   html! {
+///     <p title=title.unwrap_or_default()>{greeting}", "{name}</p>
+///   }
+/// }
+///
+/// let html = html! { <Greeting name="World" .. /> };
+/// ```
+///
+/// # Async components
+///
+/// The function can be `async`, in which case the component implements `AsyncHtmlContent`. When
+/// it's used in a template, it's resolved by the `AsyncRender` that renders the template, such as
+/// through `HtmlContent::render_async` or `AsyncHtmlContent::into_string_async`. The future of
+/// the function must be `Send`.
+///
+/// ```
+/// # use rstml_component::{html, component, HtmlContent};
+/// #[component(pub Profile)]
+/// async fn profile(id: u32) -> impl HtmlContent {
+///   let name = async { format!("user {id}") }.await;
+///   html! {
+///     <p>{name}</p>
+///   }
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn component(
 	attr: proc_macro::TokenStream,
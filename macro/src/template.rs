@@ -2,23 +2,32 @@ use self::ide::IdeHelper;
 use proc_macro2::Span;
 use proc_macro2_diagnostics::Diagnostic;
 use quote::quote;
+use quote::quote_spanned;
 use quote::ToTokens;
-use rstml::node::{NodeBlock, NodeComment, NodeName, NodeText, RawText};
+use rstml::node::{NodeBlock, NodeComment, NodeName, RawText};
 use std::collections::HashSet;
 use std::sync::OnceLock;
 use syn::parse::Parse;
 use syn::spanned::Spanned;
 use syn::Block;
 use syn::LitByteStr;
+use syn::LitStr;
 use syn::Stmt;
 use syn::{Expr, Ident, Path};
 
+mod config;
+mod entities;
+mod escape;
 mod ide;
+mod names;
 mod parsing;
+mod preprocess;
+mod whitespace;
 
 pub use parsing::TemplateParser;
 
 static DEFAULT_EMPTY_ELEMENTS: OnceLock<HashSet<&'static str>> = OnceLock::new();
+static DEFAULT_RAW_TEXT_ELEMENTS: OnceLock<HashSet<&'static str>> = OnceLock::new();
 
 fn default_empty_elements() -> &'static HashSet<&'static str> {
 	DEFAULT_EMPTY_ELEMENTS.get_or_init(|| {
@@ -31,6 +40,10 @@ fn default_empty_elements() -> &'static HashSet<&'static str> {
 	})
 }
 
+fn default_raw_text_elements() -> &'static HashSet<&'static str> {
+	DEFAULT_RAW_TEXT_ELEMENTS.get_or_init(|| ["script", "style"].into_iter().collect())
+}
+
 enum AttributeValue {
 	Constant(String),
 	Expression(Box<Expr>),
@@ -46,46 +59,126 @@ struct ComponentProp {
 	value: Expr,
 }
 
+struct ComponentAttribute {
+	name: String,
+	value: Option<AttributeValue>,
+}
+
 struct Component {
 	path: Path,
 	props: Vec<ComponentProp>,
+	attributes: Vec<ComponentAttribute>,
+	spread: Option<Box<Expr>>,
+	/// Whether the component is constructed through its builder, rather than a struct literal.
+	builder: bool,
 	children: Option<Children>,
 }
 
+/// A `class` or `style` attribute, merged with the `class:` or `style:` directives of the
+/// element.
+struct MergedAttribute {
+	name: &'static str,
+	span: Span,
+	parts: Vec<MergedPart>,
+}
+
+enum MergedPart {
+	/// The value of a plain `class` or `style` attribute.
+	Value(AttributeValue),
+
+	/// A directive, such as `class:active={condition}` or `style:color={value}`.
+	Directive(String, Box<Expr>),
+}
+
+/// A comment with interpolated values, such as `<!-- rendered by {node_id} -->`.
+struct DynamicComment {
+	span: Span,
+	/// The format string of the content, as passed to `format_args!`.
+	format: String,
+	args: Vec<Expr>,
+}
+
 enum TemplateWriteInstruction {
 	Doctype(RawText),
-	OpenTagStart(NodeName),
+	/// The start of an open tag, which is written through the formatter if the element has
+	/// dynamic attributes, so they can be merged with the other attributes of the element. The
+	/// formatter writes the attributes before anything else is written, such as the end of the tag.
+	OpenTagStart(NodeName, bool),
 	AttributeName(NodeName),
 	AttributeValue(AttributeValue),
+	/// An attribute written through the formatter, which leaves it out if the value isn't
+	/// present, and merges it with an existing attribute of the same name.
+	DynamicAttribute(NodeName, Option<AttributeValue>),
+	MergedAttribute(MergedAttribute),
 	OpenTagEnd,
 	SelfCloseTag,
 	EndTag(NodeName),
-	Text(NodeText),
+	/// Text that is already escaped.
+	Text(String),
 	RawText(RawText),
 	Comment(NodeComment),
+	DynamicComment(DynamicComment),
 	DynamicAttributes(NodeBlock),
 	DynamicContent(NodeBlock),
 	Component(Component),
+	/// Statements that don't produce any output, such as `{let total = price * count;}`.
+	Statements(Vec<Stmt>),
+	/// Instructions written in a Rust block, which limits the scope of the bindings created by
+	/// their statements.
+	Scope(Vec<TemplateWriteInstruction>),
+}
+
+/// The void and raw text elements of a template that changes the default sets.
+struct ElementSets {
+	void_elements: Vec<&'static str>,
+	raw_text_elements: Vec<&'static str>,
 }
 
 pub struct Template {
 	instructions: Vec<TemplateWriteInstruction>,
+	elements: Option<ElementSets>,
 	diagnostics: Vec<Diagnostic>,
 	ide_helper: IdeHelper,
 }
 
 impl Template {
 	pub fn parser() -> TemplateParser {
-		TemplateParser::new(default_empty_elements())
+		TemplateParser::new(Default::default())
 	}
 
 	pub fn is_empty(&self) -> bool {
 		self.instructions.is_empty() && self.diagnostics.is_empty()
 	}
 
+	/// Returns the output of the template if it doesn't depend on any runtime values, or the
+	/// span of the first part that does.
+	pub fn static_output(&self) -> Result<String, Span> {
+		let mut output = String::new();
+		for instruction in &self.instructions {
+			match instruction.static_output() {
+				Some(value) => output.push_str(&value),
+				None => return Err(instruction.dynamic_span()),
+			}
+		}
+
+		Ok(output)
+	}
+
+	/// Returns the IDE helper and diagnostics of the template, without any of its output.
+	pub fn diagnostics(&self) -> proc_macro2::TokenStream {
+		let ide_helper = &self.ide_helper;
+		let diagnostics = self
+			.diagnostics
+			.iter()
+			.map(|d| d.clone().emit_as_item_tokens());
+
+		quote!(#ide_helper #(#diagnostics)*)
+	}
+
 	pub fn with_formatter<'a>(&'a self, formatter: &'a Ident) -> impl ToTokens + 'a {
 		TemplateTokensWriter {
 			instructions: &self.instructions,
+			elements: self.elements.as_ref(),
 			diagnostics: &self.diagnostics,
 			ide_helper: &self.ide_helper,
 			formatter,
@@ -95,6 +188,7 @@ impl Template {
 
 struct TemplateTokensWriter<'a> {
 	instructions: &'a [TemplateWriteInstruction],
+	elements: Option<&'a ElementSets>,
 	diagnostics: &'a [Diagnostic],
 	ide_helper: &'a IdeHelper,
 	formatter: &'a Ident,
@@ -107,123 +201,218 @@ struct TemplateInstructionWriter<'a> {
 
 impl<'a> ToTokens for TemplateTokensWriter<'a> {
 	fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-		let instructions = self.instructions.iter().map(|i| TemplateInstructionWriter {
-			instruction: i,
-			formatter: self.formatter,
-		});
+		let formatter = self.formatter;
 		let diagnostics = self
 			.diagnostics
 			.iter()
 			.map(|d| d.clone().emit_as_item_tokens());
 		let ide_helper = self.ide_helper;
 
-		tokens.extend(quote! {
-			#ide_helper
-			#(#instructions)*
-			#(#diagnostics)*
-		});
+		tokens.extend(quote!(#ide_helper));
+		match self.elements {
+			// the content is written with the element sets of the template, so that elements
+			// written at runtime are treated the same way as the elements of the template
+			Some(ElementSets {
+				void_elements,
+				raw_text_elements,
+			}) if self
+				.instructions
+				.iter()
+				.any(|instruction| instruction.static_output().is_none()) =>
+			{
+				let mut content = proc_macro2::TokenStream::new();
+				write_instructions(self.instructions, formatter, &mut content);
+				tokens.extend(quote! {
+					#formatter.write_with_elements(
+						{
+							static ELEMENTS: ::rstml_component::ElementConfig = ::rstml_component::ElementConfig::new(
+								&[#(#void_elements),*],
+								&[#(#raw_text_elements),*],
+							);
+							&ELEMENTS
+						},
+						|#formatter: &mut ::rstml_component::HtmlFormatter| -> ::std::fmt::Result {
+							#content
+							Ok(())
+						},
+					)?;
+				});
+			}
+			_ => write_instructions(self.instructions, formatter, tokens),
+		}
+		tokens.extend(quote!(#(#diagnostics)*));
 	}
 }
 
-impl<'a> ToTokens for TemplateInstructionWriter<'a> {
-	fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-		let formatter = self.formatter;
-		match self.instruction {
-			TemplateWriteInstruction::Doctype(doctype) => {
-				let value = &doctype.to_token_stream_string();
-				let value = LitByteStr::new(value.as_bytes(), doctype.span());
-				tokens.extend(quote!(#formatter.write_doctype(#value);));
+fn write_instructions(
+	instructions: &[TemplateWriteInstruction],
+	formatter: &Ident,
+	tokens: &mut proc_macro2::TokenStream,
+) {
+	// consecutive static instructions are merged into a single write
+	let mut static_output = String::new();
+	let flush = |static_output: &mut String, tokens: &mut proc_macro2::TokenStream| {
+		if !static_output.is_empty() {
+			let value = LitByteStr::new(static_output.as_bytes(), Span::call_site());
+			tokens.extend(quote!(#formatter.write_bytes(#value);));
+			static_output.clear();
+		}
+	};
+
+	for instruction in instructions {
+		match instruction.static_output() {
+			Some(output) => static_output.push_str(&output),
+			None => {
+				flush(&mut static_output, tokens);
+				TemplateInstructionWriter {
+					instruction,
+					formatter,
+				}
+				.to_tokens(tokens);
 			}
+		}
+	}
 
-			TemplateWriteInstruction::OpenTagStart(name) => {
-				let value = name.to_string();
-				let value = LitByteStr::new(value.as_bytes(), Span::call_site());
-				tokens.extend(quote!(#formatter.write_open_tag_start(#value);));
-			}
+	flush(&mut static_output, tokens);
+}
 
-			TemplateWriteInstruction::AttributeName(name) => {
-				let value = name.to_string();
-				let value = LitByteStr::new(value.as_bytes(), Span::call_site());
-				tokens.extend(quote!(#formatter.write_attribute_name(#value);));
+impl TemplateWriteInstruction {
+	/// Returns the span of a dynamic instruction, for diagnostics.
+	fn dynamic_span(&self) -> Span {
+		match self {
+			TemplateWriteInstruction::AttributeValue(AttributeValue::Expression(expr)) => expr.span(),
+			TemplateWriteInstruction::OpenTagStart(name, _)
+			| TemplateWriteInstruction::DynamicAttribute(name, _) => name.span(),
+			TemplateWriteInstruction::MergedAttribute(attribute) => attribute.span,
+			TemplateWriteInstruction::DynamicComment(comment) => comment.span,
+			TemplateWriteInstruction::DynamicAttributes(block)
+			| TemplateWriteInstruction::DynamicContent(block) => block.span(),
+			TemplateWriteInstruction::Component(component) => component.path.span(),
+			TemplateWriteInstruction::Statements(stmts) => stmts[0].span(),
+			TemplateWriteInstruction::Scope(instructions) => instructions
+				.iter()
+				.find(|instruction| instruction.static_output().is_none())
+				.map_or_else(Span::call_site, TemplateWriteInstruction::dynamic_span),
+			_ => Span::call_site(),
+		}
+	}
+
+	/// Returns the output of the instruction if it doesn't depend on any runtime values. Values
+	/// are escaped the same way the formatter escapes them at runtime.
+	fn static_output(&self) -> Option<String> {
+		let output = match self {
+			TemplateWriteInstruction::Doctype(doctype) => {
+				format!(
+					"<!DOCTYPE {}>",
+					escape::text(&doctype.to_token_stream_string())
+				)
+			}
+			TemplateWriteInstruction::OpenTagStart(name, false) => format!("<{}", name),
+			TemplateWriteInstruction::AttributeName(name) => format!(" {}", name),
+			TemplateWriteInstruction::AttributeValue(AttributeValue::Constant(value)) => {
+				format!("=\"{}\"", escape::attribute(value))
 			}
+			TemplateWriteInstruction::OpenTagEnd => ">".to_owned(),
+			TemplateWriteInstruction::SelfCloseTag => " />".to_owned(),
+			TemplateWriteInstruction::EndTag(name) => format!("</{}>", name),
+			TemplateWriteInstruction::Text(content) => content.clone(),
+			TemplateWriteInstruction::RawText(content) => escape::raw_text(&content.to_string_best()),
+			TemplateWriteInstruction::Comment(comment) => {
+				format!("<!--{}-->", escape::text(&comment.value.value()))
+			}
+			_ => return None,
+		};
+
+		Some(output)
+	}
+}
 
+impl<'a> ToTokens for TemplateInstructionWriter<'a> {
+	fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+		let formatter = self.formatter;
+		match self.instruction {
 			TemplateWriteInstruction::AttributeValue(expr) => {
 				tokens.extend(quote!(#formatter.write_attribute_value(#expr)?;));
 			}
 
-			TemplateWriteInstruction::OpenTagEnd => {
-				tokens.extend(quote!(#formatter.write_open_tag_end();));
+			TemplateWriteInstruction::OpenTagStart(name, _) => {
+				let name = LitByteStr::new(name.to_string().as_bytes(), name.span());
+				tokens.extend(quote!(#formatter.write_open_tag_start(#name);));
 			}
 
-			TemplateWriteInstruction::SelfCloseTag => {
-				tokens.extend(quote!(#formatter.write_self_close_tag();));
-			}
+			TemplateWriteInstruction::DynamicAttribute(name, value) => {
+				let name = LitByteStr::new(name.to_string().as_bytes(), name.span());
+				let value = match value {
+					Some(value) => quote!(#value),
+					None => quote!(()),
+				};
 
-			TemplateWriteInstruction::EndTag(name) => {
-				let value = name.to_string();
-				let value = LitByteStr::new(value.as_bytes(), Span::call_site());
-				tokens.extend(quote!(#formatter.write_end_tag(#value);));
+				tokens.extend(quote!(#formatter.write_attribute(#name, #value)?;));
 			}
 
-			TemplateWriteInstruction::Text(content) => {
-				let value = content.value_string();
-				let value = LitByteStr::new(value.as_bytes(), content.span());
-				tokens.extend(quote!(#formatter.write_bytes(#value);));
+			TemplateWriteInstruction::MergedAttribute(MergedAttribute { name, span, parts }) => {
+				let (list, directive) = match *name {
+					"class" => (quote!(Classes), quote!(with_if)),
+					_ => (quote!(Styles), quote!(property)),
+				};
+
+				let parts = parts.iter().map(|part| match part {
+					MergedPart::Value(value) => quote!(.with(#value)?),
+					MergedPart::Directive(name, value) => quote!(.#directive(#name, #value)?),
+				});
+
+				let name = LitByteStr::new(name.as_bytes(), *span);
+				tokens.extend(quote! {
+					#formatter.write_attribute(#name, ::rstml_component::#list::new()#(#parts)*)?;
+				});
 			}
 
-			TemplateWriteInstruction::RawText(content) => {
-				let value = content.to_string_best();
-				let value = LitByteStr::new(value.as_bytes(), content.span());
-				tokens.extend(quote!(#formatter.write_bytes(#value);));
-			}
-
-			TemplateWriteInstruction::Comment(comment) => {
-				let value = &comment.value;
-				let value = LitByteStr::new(value.value().as_bytes(), comment.value.span());
-				tokens.extend(quote!(#formatter.write_comment(#value);));
+			TemplateWriteInstruction::DynamicComment(DynamicComment { span, format, args }) => {
+				let format = LitStr::new(format, *span);
+				tokens.extend(quote_spanned! {*span=>
+					#formatter.write_comment_fmt(::std::format_args!(#format, #(#args),*))?;
+				});
 			}
 
 			TemplateWriteInstruction::DynamicAttributes(content) => {
-				match content {
-					NodeBlock::ValidBlock(Block { stmts, .. }) if stmts.len() == 1 => {
-						if let Stmt::Expr(expr, None) = &stmts[0] {
-							tokens.extend(quote!(#formatter.write_attributes(#expr)?;));
-							return;
-						}
-					}
-					_ => (),
-				}
-
+				let content = BlockValue(content);
 				tokens.extend(quote!(#formatter.write_attributes(#content)?;));
 			}
 
 			TemplateWriteInstruction::DynamicContent(content) => {
-				match content {
-					NodeBlock::ValidBlock(Block { stmts, .. }) if stmts.len() == 1 => {
-						if let Stmt::Expr(expr, None) = &stmts[0] {
-							tokens.extend(quote!(#formatter.write_content(#expr)?;));
-							return;
-						}
-					}
-					_ => (),
-				}
-
+				let content = BlockValue(content);
 				tokens.extend(quote!(#formatter.write_content(#content)?;));
 			}
 
+			TemplateWriteInstruction::Statements(stmts) => {
+				tokens.extend(quote!(#(#stmts)*));
+			}
+
+			TemplateWriteInstruction::Scope(instructions) => {
+				let mut scope = proc_macro2::TokenStream::new();
+				write_instructions(instructions, formatter, &mut scope);
+				tokens.extend(quote!({ #scope }));
+			}
+
 			TemplateWriteInstruction::Component(Component {
 				path: name,
 				props,
+				attributes,
+				spread,
+				builder,
 				children,
 			}) => {
 				let mut props = props
 					.iter()
-					.map(|ComponentProp { name, value }| quote!(#name: #value))
+					.map(|ComponentProp { name, value }| (quote!(#name), quote!(#value)))
 					.collect::<Vec<_>>();
 
 				if let Some(children) = children {
 					let children = match children {
-						Children::Expr(expr) => quote!(#expr),
+						Children::Expr(expr) => {
+							let expr = BlockValue(expr);
+							quote!(#expr)
+						}
 						Children::Template(template) => {
 							let template = template.with_formatter(formatter);
 							quote! {
@@ -235,15 +424,82 @@ impl<'a> ToTokens for TemplateInstructionWriter<'a> {
 						}
 					};
 
-					props.push(quote!(children: #children));
+					props.push((quote!(children), children));
+				}
+
+				if !attributes.is_empty() {
+					let attributes_formatter = Ident::new("__attrs", Span::call_site());
+					let attributes = attributes.iter().map(|ComponentAttribute { name, value }| {
+						let name = LitByteStr::new(name.as_bytes(), Span::call_site());
+						let value = match value {
+							Some(value) => quote!(#value),
+							None => quote!(()),
+						};
+
+						quote!(#attributes_formatter.write_attribute(#name, #value)?;)
+					});
+
+					props.push((
+						quote!(attrs),
+						quote! {
+							|#attributes_formatter: &mut ::rstml_component::HtmlAttributesFormatter| -> ::std::fmt::Result {
+								#(#attributes)*
+								Ok(())
+							}
+						},
+					));
 				}
 
-				tokens.extend(quote!(#formatter.write_content(#name { #(#props),* })?;));
+				let component = match spread {
+					// props missing from the component are taken from the spread using struct update
+					// syntax
+					Some(spread) => {
+						let props = props.iter().map(|(name, value)| quote!(#name: #value));
+						quote!(#name { #(#props,)* ..#spread })
+					}
+
+					// the builder takes care of optional props and conversions
+					None if *builder => {
+						let setters = props.iter().map(|(name, value)| quote!(.#name(#value)));
+						let build = quote_spanned!(name.span()=> build);
+						quote!(#name::builder() #(#setters)* .#build())
+					}
+
+					None => {
+						let props = props.iter().map(|(name, value)| quote!(#name: #value));
+						quote!(#name { #(#props,)* })
+					}
+				};
+
+				tokens.extend(quote!(#formatter.write_content(#component)?;));
 			}
+
+			// static instructions are merged by `TemplateTokensWriter`
+			_ => unreachable!("static instructions are written by the template writer"),
 		}
 	}
 }
 
+/// Writes the expression of a block containing a single expression without its braces, and
+/// any other block as is.
+struct BlockValue<'a>(&'a NodeBlock);
+
+impl<'a> ToTokens for BlockValue<'a> {
+	fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+		match self.0 {
+			NodeBlock::ValidBlock(Block { stmts, .. }) if stmts.len() == 1 => {
+				if let Stmt::Expr(expr, None) = &stmts[0] {
+					expr.to_tokens(tokens);
+					return;
+				}
+			}
+			_ => (),
+		}
+
+		self.0.to_tokens(tokens);
+	}
+}
+
 impl ToTokens for AttributeValue {
 	fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
 		match self {
@@ -262,6 +518,6 @@ impl ToTokens for AttributeValue {
 
 impl Parse for Template {
 	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-		Ok(Template::parser().parse_syn_stream(input))
+		Template::parser().parse_syn_stream(input)
 	}
 }
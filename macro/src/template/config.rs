@@ -0,0 +1,251 @@
+//! Configuration of a template, given by `#![html(...)]` attributes at the start of the
+//! template:
+//!
+//! ```ignore
+//! html! {
+//!   #![html(validate, allow_elements("turbo-frame"), allow_attributes("hx-*"))]
+//!   <div hx-get="/more">...</div>
+//! }
+//! ```
+
+use super::{
+	default_empty_elements, default_raw_text_elements, whitespace::Whitespace, ElementSets,
+};
+use proc_macro2::TokenStream;
+use std::{
+	collections::{HashMap, HashSet},
+	sync::{Mutex, OnceLock},
+};
+use syn::{
+	parenthesized, parse::Parser, punctuated::Punctuated, Attribute, Ident, LitBool, LitStr, Token,
+};
+
+#[derive(Clone)]
+pub struct TemplateConfig {
+	/// Whether element and attribute names are validated against the HTML standard.
+	pub validate: bool,
+
+	/// Additional element names that are allowed when validating. A trailing `*` matches any
+	/// suffix.
+	pub allowed_elements: Vec<String>,
+
+	/// Additional attribute names that are allowed when validating. A trailing `*` matches any
+	/// suffix.
+	pub allowed_attributes: Vec<String>,
+
+	/// Elements that can't have children, and are always self-closed.
+	pub empty_elements: HashSet<&'static str>,
+
+	/// Elements whose content is written as is, rather than parsed as a template.
+	pub raw_text_elements: HashSet<&'static str>,
+
+	/// How whitespace in text is handled.
+	pub whitespace: Whitespace,
+
+	/// How whitespace in text is handled inside specific elements, overriding `whitespace`.
+	pub element_whitespace: HashMap<String, Whitespace>,
+
+	/// Whether comments are left out of the output.
+	pub strip_comments: bool,
+}
+
+impl Default for TemplateConfig {
+	fn default() -> Self {
+		Self {
+			validate: false,
+			allowed_elements: Vec::new(),
+			allowed_attributes: Vec::new(),
+			empty_elements: default_empty_elements().clone(),
+			raw_text_elements: default_raw_text_elements().clone(),
+			whitespace: Whitespace::default(),
+			element_whitespace: [
+				("pre".to_owned(), Whitespace::Preserve),
+				("textarea".to_owned(), Whitespace::Preserve),
+			]
+			.into_iter()
+			.collect(),
+			strip_comments: false,
+		}
+	}
+}
+
+impl TemplateConfig {
+	/// Returns the void and raw text elements, if either differs from the defaults.
+	pub fn changed_elements(&self) -> Option<ElementSets> {
+		if self.empty_elements == *default_empty_elements()
+			&& self.raw_text_elements == *default_raw_text_elements()
+		{
+			return None;
+		}
+
+		let sorted = |elements: &HashSet<&'static str>| {
+			let mut elements = elements.iter().copied().collect::<Vec<_>>();
+			elements.sort_unstable();
+			elements
+		};
+
+		Some(ElementSets {
+			void_elements: sorted(&self.empty_elements),
+			raw_text_elements: sorted(&self.raw_text_elements),
+		})
+	}
+
+	/// Splits the configuration attributes from the start of the template, and applies them to
+	/// the configuration. Returns the remaining tokens of the template.
+	pub fn parse_header(&mut self, tokens: TokenStream) -> syn::Result<TokenStream> {
+		let parser = |input: syn::parse::ParseStream| {
+			let attrs = if input.peek(Token![#]) && input.peek2(Token![!]) {
+				input.call(Attribute::parse_inner)?
+			} else {
+				Vec::new()
+			};
+
+			let rest: TokenStream = input.parse()?;
+			Ok((attrs, rest))
+		};
+
+		let (attrs, rest) = parser.parse2(tokens)?;
+		for attr in attrs {
+			self.apply(&attr)?;
+		}
+
+		Ok(rest)
+	}
+
+	fn apply(&mut self, attr: &Attribute) -> syn::Result<()> {
+		if !attr.path().is_ident("html") {
+			return Err(syn::Error::new_spanned(
+				attr.path(),
+				"unknown template attribute, expected `#![html(...)]`",
+			));
+		}
+
+		attr.parse_nested_meta(|meta| {
+			if meta.path.is_ident("validate") {
+				self.validate = parse_flag(&meta)?;
+				Ok(())
+			} else if meta.path.is_ident("strip_comments") {
+				self.strip_comments = parse_flag(&meta)?;
+				Ok(())
+			} else if meta.path.is_ident("allow_elements") {
+				self.allowed_elements.extend(parse_names(&meta)?);
+				Ok(())
+			} else if meta.path.is_ident("allow_attributes") {
+				self.allowed_attributes.extend(parse_names(&meta)?);
+				Ok(())
+			} else if meta.path.is_ident("void_elements") {
+				let names = parse_names(&meta)?;
+				self.empty_elements.extend(names.into_iter().map(intern));
+				Ok(())
+			} else if meta.path.is_ident("non_void_elements") {
+				for name in parse_names(&meta)? {
+					self.empty_elements.remove(&*name);
+				}
+				Ok(())
+			} else if meta.path.is_ident("raw_text_elements") {
+				let names = parse_names(&meta)?;
+				self.raw_text_elements.extend(names.into_iter().map(intern));
+				Ok(())
+			} else if meta.path.is_ident("non_raw_text_elements") {
+				for name in parse_names(&meta)? {
+					self.raw_text_elements.remove(&*name);
+				}
+				Ok(())
+			} else if meta.path.is_ident("whitespace") {
+				if meta.input.peek(Token![=]) {
+					self.whitespace = Whitespace::parse(&meta.value()?.parse()?)?;
+				} else {
+					let content;
+					parenthesized!(content in meta.input);
+					let modes = Punctuated::<ElementWhitespace, Token![,]>::parse_terminated(&content)?;
+					self
+						.element_whitespace
+						.extend(modes.into_iter().map(|mode| (mode.element, mode.whitespace)));
+				}
+				Ok(())
+			} else {
+				Err(meta.error(
+					"unknown template attribute, expected `validate`, `allow_elements`, `allow_attributes`, `void_elements`, `non_void_elements`, `raw_text_elements`, `non_raw_text_elements`, `whitespace` or `strip_comments`",
+				))
+			}
+		})
+	}
+
+	pub fn is_allowed_element(&self, name: &str) -> bool {
+		matches_any(&self.allowed_elements, name)
+	}
+
+	pub fn is_allowed_attribute(&self, name: &str) -> bool {
+		matches_any(&self.allowed_attributes, name)
+	}
+}
+
+/// The whitespace mode of an element, such as `pre = "preserve"` or `"my-code" = "collapse"`.
+struct ElementWhitespace {
+	element: String,
+	whitespace: Whitespace,
+}
+
+impl syn::parse::Parse for ElementWhitespace {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let element = if input.peek(LitStr) {
+			input.parse::<LitStr>()?.value()
+		} else {
+			input.parse::<Ident>()?.to_string()
+		};
+
+		input.parse::<Token![=]>()?;
+		let whitespace = Whitespace::parse(&input.parse()?)?;
+		Ok(Self {
+			element,
+			whitespace,
+		})
+	}
+}
+
+/// Parses a flag, which is either given on its own (`validate`) or with a value
+/// (`validate = false`).
+fn parse_flag(meta: &syn::meta::ParseNestedMeta) -> syn::Result<bool> {
+	if meta.input.peek(Token![=]) {
+		Ok(meta.value()?.parse::<LitBool>()?.value)
+	} else {
+		Ok(true)
+	}
+}
+
+/// Parses a list of names, such as `("hx-get", "hx-post")`.
+fn parse_names(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Vec<String>> {
+	let content;
+	parenthesized!(content in meta.input);
+	let names = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+	Ok(names.iter().map(LitStr::value).collect())
+}
+
+fn matches_any(patterns: &[String], name: &str) -> bool {
+	patterns
+		.iter()
+		.any(|pattern| match pattern.strip_suffix('*') {
+			Some(prefix) => name.starts_with(prefix),
+			None => pattern == name,
+		})
+}
+
+/// The element sets given to rstml require `'static` names, so configured names are interned to
+/// avoid leaking a copy for every template.
+fn intern(name: String) -> &'static str {
+	static NAMES: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+	let mut names = NAMES
+		.get_or_init(Default::default)
+		.lock()
+		.unwrap_or_else(|err| err.into_inner());
+
+	match names.get(&*name) {
+		Some(name) => name,
+		None => {
+			let name: &'static str = Box::leak(name.into_boxed_str());
+			names.insert(name);
+			name
+		}
+	}
+}
@@ -0,0 +1,102 @@
+//! Compile-time counterparts of the escaping functions used by the formatter at runtime, so
+//! that static output can be escaped while expanding the template.
+
+use super::entities::is_known_entity;
+
+/// Escapes `<`, `>`, `&`, `'` and `"`, like the escaping of attribute values.
+pub fn attribute(raw: &str) -> String {
+	escape(raw, |ch| matches!(ch, '<' | '>' | '&' | '\'' | '"'))
+}
+
+/// Escapes `<`, `>` and `&`, like the escaping of text content.
+pub fn text(raw: &str) -> String {
+	escape(raw, |ch| matches!(ch, '<' | '>' | '&'))
+}
+
+/// Escapes `<`, `>` and `&` in static template text, except for `&` starting a character
+/// reference such as `&copy;`, `&#169;` or `&#xA9;`. Returns the escaped text and the references
+/// that are not valid, which are escaped like other text.
+pub fn text_with_entities(raw: &str) -> (String, Vec<String>) {
+	let mut escaped = String::with_capacity(raw.len());
+	let mut invalid = Vec::new();
+	let mut rest = raw;
+	while let Some(i) = rest.find(['<', '>', '&']) {
+		escaped.push_str(&rest[..i]);
+		rest = &rest[i..];
+
+		if let Some(len) = reference_len(rest) {
+			let reference = &rest[..len];
+			if is_valid_reference(reference) {
+				escaped.push_str(reference);
+			} else {
+				escaped.push_str(&text(reference));
+				invalid.push(reference.to_owned());
+			}
+
+			rest = &rest[len..];
+		} else {
+			escaped.push_str(&text(&rest[..1]));
+			rest = &rest[1..];
+		}
+	}
+
+	escaped.push_str(rest);
+	(escaped, invalid)
+}
+
+/// Escapes `</` as `<\/`, like the escaping of the content of `script` and `style` elements.
+pub fn raw_text(raw: &str) -> String {
+	raw.replace("</", "<\\/")
+}
+
+/// Returns the length of the character reference at the start of `text`, which is an `&`
+/// followed by a name or a `#` and a number, and a `;`.
+fn reference_len(text: &str) -> Option<usize> {
+	let body = text.strip_prefix('&')?;
+	let end = body.find(';')?;
+	let name = &body[..end];
+	let is_reference = match name.strip_prefix('#') {
+		Some(number) => !number.is_empty() && number.chars().all(|ch| ch.is_ascii_alphanumeric()),
+		None => {
+			name.starts_with(|ch: char| ch.is_ascii_alphabetic())
+				&& name.chars().all(|ch| ch.is_ascii_alphanumeric())
+		}
+	};
+
+	is_reference.then_some(end + 2)
+}
+
+fn is_valid_reference(reference: &str) -> bool {
+	let name = &reference[1..reference.len() - 1];
+	let Some(number) = name.strip_prefix('#') else {
+		return is_known_entity(name);
+	};
+
+	let code = match number.strip_prefix(['x', 'X']) {
+		Some(hex) => u32::from_str_radix(hex, 16),
+		None => number.parse(),
+	};
+
+	matches!(code.ok().and_then(char::from_u32), Some(ch) if ch != '\0')
+}
+
+fn escape(raw: &str, escape_char: impl Fn(char) -> bool) -> String {
+	let mut escaped = String::with_capacity(raw.len());
+	for ch in raw.chars() {
+		if !escape_char(ch) {
+			escaped.push(ch);
+			continue;
+		}
+
+		escaped.push_str(match ch {
+			'<' => "&lt;",
+			'>' => "&gt;",
+			'&' => "&amp;",
+			'\'' => "&apos;",
+			'"' => "&quot;",
+			_ => unreachable!("Only '<', '>', '&', '\\'' and '\"' are escaped"),
+		});
+	}
+
+	escaped
+}
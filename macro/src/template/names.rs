@@ -0,0 +1,475 @@
+//! Element and attribute names from the HTML living standard, used to validate the names used
+//! in templates.
+
+/// Known elements, with the attributes that are specific to them.
+const ELEMENTS: &[(&str, &[&str])] = &[
+	(
+		"a",
+		&[
+			"href",
+			"target",
+			"download",
+			"ping",
+			"rel",
+			"hreflang",
+			"type",
+			"referrerpolicy",
+		],
+	),
+	("abbr", &[]),
+	("address", &[]),
+	(
+		"area",
+		&[
+			"alt",
+			"coords",
+			"shape",
+			"href",
+			"target",
+			"download",
+			"ping",
+			"rel",
+			"referrerpolicy",
+		],
+	),
+	("article", &[]),
+	("aside", &[]),
+	(
+		"audio",
+		&[
+			"src",
+			"crossorigin",
+			"preload",
+			"autoplay",
+			"loop",
+			"muted",
+			"controls",
+		],
+	),
+	("b", &[]),
+	("base", &["href", "target"]),
+	("bdi", &[]),
+	("bdo", &[]),
+	("blockquote", &["cite"]),
+	("body", &[]),
+	("br", &[]),
+	(
+		"button",
+		&[
+			"command",
+			"commandfor",
+			"disabled",
+			"form",
+			"formaction",
+			"formenctype",
+			"formmethod",
+			"formnovalidate",
+			"formtarget",
+			"name",
+			"popovertarget",
+			"popovertargetaction",
+			"type",
+			"value",
+		],
+	),
+	("canvas", &["width", "height"]),
+	("caption", &[]),
+	("cite", &[]),
+	("code", &[]),
+	("col", &["span"]),
+	("colgroup", &["span"]),
+	("data", &["value"]),
+	("datalist", &[]),
+	("dd", &[]),
+	("del", &["cite", "datetime"]),
+	("details", &["name", "open"]),
+	("dfn", &[]),
+	("dialog", &["open", "closedby"]),
+	("div", &[]),
+	("dl", &[]),
+	("dt", &[]),
+	("em", &[]),
+	("embed", &["src", "type", "width", "height"]),
+	("fieldset", &["disabled", "form", "name"]),
+	("figcaption", &[]),
+	("figure", &[]),
+	("footer", &[]),
+	(
+		"form",
+		&[
+			"accept-charset",
+			"action",
+			"autocomplete",
+			"enctype",
+			"method",
+			"name",
+			"novalidate",
+			"rel",
+			"target",
+		],
+	),
+	("h1", &[]),
+	("h2", &[]),
+	("h3", &[]),
+	("h4", &[]),
+	("h5", &[]),
+	("h6", &[]),
+	("head", &[]),
+	("header", &[]),
+	("hgroup", &[]),
+	("hr", &[]),
+	("html", &[]),
+	("i", &[]),
+	(
+		"iframe",
+		&[
+			"src",
+			"srcdoc",
+			"name",
+			"sandbox",
+			"allow",
+			"allowfullscreen",
+			"width",
+			"height",
+			"referrerpolicy",
+			"loading",
+		],
+	),
+	(
+		"img",
+		&[
+			"alt",
+			"src",
+			"srcset",
+			"sizes",
+			"crossorigin",
+			"usemap",
+			"ismap",
+			"width",
+			"height",
+			"referrerpolicy",
+			"decoding",
+			"loading",
+			"fetchpriority",
+		],
+	),
+	(
+		"input",
+		&[
+			"accept",
+			"alpha",
+			"alt",
+			"autocomplete",
+			"checked",
+			"colorspace",
+			"dirname",
+			"disabled",
+			"form",
+			"formaction",
+			"formenctype",
+			"formmethod",
+			"formnovalidate",
+			"formtarget",
+			"height",
+			"list",
+			"max",
+			"maxlength",
+			"min",
+			"minlength",
+			"multiple",
+			"name",
+			"pattern",
+			"placeholder",
+			"popovertarget",
+			"popovertargetaction",
+			"readonly",
+			"required",
+			"size",
+			"src",
+			"step",
+			"type",
+			"value",
+			"width",
+		],
+	),
+	("ins", &["cite", "datetime"]),
+	("kbd", &[]),
+	("label", &["for"]),
+	("legend", &[]),
+	("li", &["value"]),
+	(
+		"link",
+		&[
+			"href",
+			"crossorigin",
+			"rel",
+			"as",
+			"media",
+			"hreflang",
+			"type",
+			"sizes",
+			"imagesrcset",
+			"imagesizes",
+			"referrerpolicy",
+			"integrity",
+			"blocking",
+			"color",
+			"disabled",
+			"fetchpriority",
+		],
+	),
+	("main", &[]),
+	("map", &["name"]),
+	("mark", &[]),
+	("math", &[]),
+	("menu", &[]),
+	(
+		"meta",
+		&["name", "http-equiv", "content", "charset", "media"],
+	),
+	("meter", &["value", "min", "max", "low", "high", "optimum"]),
+	("nav", &[]),
+	("noscript", &[]),
+	(
+		"object",
+		&["data", "type", "name", "form", "width", "height"],
+	),
+	("ol", &["reversed", "start", "type"]),
+	("optgroup", &["disabled", "label"]),
+	("option", &["disabled", "label", "selected", "value"]),
+	("output", &["for", "form", "name"]),
+	("p", &[]),
+	("param", &["name", "value"]),
+	("picture", &[]),
+	("pre", &[]),
+	("progress", &["value", "max"]),
+	("q", &["cite"]),
+	("rp", &[]),
+	("rt", &[]),
+	("ruby", &[]),
+	("s", &[]),
+	("samp", &[]),
+	(
+		"script",
+		&[
+			"src",
+			"type",
+			"nomodule",
+			"async",
+			"defer",
+			"crossorigin",
+			"integrity",
+			"referrerpolicy",
+			"blocking",
+			"fetchpriority",
+		],
+	),
+	("search", &[]),
+	("section", &[]),
+	(
+		"select",
+		&[
+			"autocomplete",
+			"disabled",
+			"form",
+			"multiple",
+			"name",
+			"required",
+			"size",
+		],
+	),
+	("slot", &["name"]),
+	("small", &[]),
+	(
+		"source",
+		&["type", "media", "src", "srcset", "sizes", "width", "height"],
+	),
+	("span", &[]),
+	("strong", &[]),
+	("style", &["media", "blocking"]),
+	("sub", &[]),
+	("summary", &[]),
+	("sup", &[]),
+	("svg", &[]),
+	("table", &[]),
+	("tbody", &[]),
+	("td", &["colspan", "rowspan", "headers"]),
+	(
+		"template",
+		&[
+			"shadowrootmode",
+			"shadowrootdelegatesfocus",
+			"shadowrootclonable",
+			"shadowrootserializable",
+		],
+	),
+	(
+		"textarea",
+		&[
+			"autocomplete",
+			"cols",
+			"dirname",
+			"disabled",
+			"form",
+			"maxlength",
+			"minlength",
+			"name",
+			"placeholder",
+			"readonly",
+			"required",
+			"rows",
+			"wrap",
+		],
+	),
+	("tfoot", &[]),
+	("th", &["colspan", "rowspan", "headers", "scope", "abbr"]),
+	("thead", &[]),
+	("time", &["datetime"]),
+	("title", &[]),
+	("tr", &[]),
+	("track", &["default", "kind", "label", "src", "srclang"]),
+	("u", &[]),
+	("ul", &[]),
+	("var", &[]),
+	(
+		"video",
+		&[
+			"src",
+			"crossorigin",
+			"poster",
+			"preload",
+			"autoplay",
+			"playsinline",
+			"loop",
+			"muted",
+			"controls",
+			"width",
+			"height",
+		],
+	),
+	("wbr", &[]),
+];
+
+/// Attributes that are allowed on all elements.
+const GLOBAL_ATTRIBUTES: &[&str] = &[
+	"accesskey",
+	"autocapitalize",
+	"autocorrect",
+	"autofocus",
+	"class",
+	"contenteditable",
+	"dir",
+	"draggable",
+	"enterkeyhint",
+	"hidden",
+	"id",
+	"inert",
+	"inputmode",
+	"is",
+	"itemid",
+	"itemprop",
+	"itemref",
+	"itemscope",
+	"itemtype",
+	"lang",
+	"nonce",
+	"popover",
+	"role",
+	"slot",
+	"spellcheck",
+	"style",
+	"tabindex",
+	"title",
+	"translate",
+	"writingsuggestions",
+	"xmlns",
+];
+
+/// Elements whose content is foreign (SVG and MathML), and isn't validated.
+pub const FOREIGN_ELEMENTS: &[&str] = &["svg", "math"];
+
+fn element_attributes(element: &str) -> Option<&'static [&'static str]> {
+	ELEMENTS
+		.iter()
+		.find(|(name, _)| *name == element)
+		.map(|(_, attributes)| *attributes)
+}
+
+/// Returns whether the element is part of the HTML standard, or is a custom element (which
+/// must contain a hyphen).
+pub fn is_known_element(element: &str) -> bool {
+	element_attributes(element).is_some() || element.contains('-')
+}
+
+/// Returns whether the attribute is allowed on the element. Global attributes, event handlers,
+/// `data-*` and `aria-*` attributes and the shorthands of frameworks such as Alpine.js (which
+/// start with `@` or `:`) are allowed on all elements, and any attribute is allowed on elements
+/// that aren't part of the HTML standard (such as custom elements).
+pub fn is_known_attribute(element: &str, attribute: &str) -> bool {
+	GLOBAL_ATTRIBUTES.contains(&attribute)
+		|| attribute.starts_with("data-")
+		|| attribute.starts_with("aria-")
+		|| attribute.starts_with(['@', ':'])
+		|| is_event_handler(attribute)
+		|| element_attributes(element).is_none_or(|attributes| attributes.contains(&attribute))
+}
+
+fn is_event_handler(attribute: &str) -> bool {
+	attribute
+		.strip_prefix("on")
+		.is_some_and(|event| !event.is_empty() && event.bytes().all(|b| b.is_ascii_lowercase()))
+}
+
+/// Suggests a known element with a name similar to the given one.
+pub fn similar_element(element: &str) -> Option<&'static str> {
+	similar(element, ELEMENTS.iter().map(|(name, _)| *name))
+}
+
+/// Suggests an attribute allowed on the element with a name similar to the given one.
+pub fn similar_attribute(element: &str, attribute: &str) -> Option<&'static str> {
+	let specific = element_attributes(element).unwrap_or_default();
+	similar(attribute, GLOBAL_ATTRIBUTES.iter().chain(specific).copied())
+}
+
+/// Suggests a named character reference similar to the given one, without the `&` and `;`.
+pub fn similar_entity(name: &str) -> Option<&'static str> {
+	similar(name, super::entities::ENTITIES.iter().copied())
+}
+
+fn similar(name: &str, candidates: impl Iterator<Item = &'static str>) -> Option<&'static str> {
+	candidates
+		.map(|candidate| (edit_distance(name, candidate), candidate))
+		.filter(|(distance, _)| *distance <= 2)
+		.min_by_key(|(distance, _)| *distance)
+		.map(|(_, candidate)| candidate)
+}
+
+/// Optimal string alignment distance, where swapping two adjacent characters counts as a
+/// single edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+	let (a, b) = (a.as_bytes(), b.as_bytes());
+	let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+	for (i, row) in distances.iter_mut().enumerate() {
+		row[0] = i;
+	}
+	for (j, distance) in distances[0].iter_mut().enumerate() {
+		*distance = j;
+	}
+
+	for i in 1..=a.len() {
+		for j in 1..=b.len() {
+			let cost = usize::from(a[i - 1] != b[j - 1]);
+			let mut distance = (distances[i - 1][j] + 1)
+				.min(distances[i][j - 1] + 1)
+				.min(distances[i - 1][j - 1] + cost);
+
+			if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+				distance = distance.min(distances[i - 2][j - 2] + 1);
+			}
+
+			distances[i][j] = distance;
+		}
+	}
+
+	distances[a.len()][b.len()]
+}
@@ -1,8 +1,18 @@
-use crate::template::{Component, ComponentProp};
+use crate::template::{Component, ComponentAttribute, ComponentProp};
 
-use super::{ide::IdeHelper, AttributeValue, Children, Template, TemplateWriteInstruction};
-use proc_macro2::{Ident, Span};
+use super::{
+	config::TemplateConfig,
+	escape,
+	ide::IdeHelper,
+	names,
+	preprocess::{preprocess, Placeholders},
+	whitespace::{self, Gap, Whitespace},
+	AttributeValue, Children, DynamicComment, MergedAttribute, MergedPart, Template,
+	TemplateWriteInstruction,
+};
+use proc_macro2::{Delimiter, Group, Ident, Span, TokenStream, TokenTree};
 use proc_macro2_diagnostics::{Diagnostic, SpanDiagnosticExt};
+use quote::ToTokens;
 use rstml::{
 	node::{
 		AttributeValueExpr, FnBinding, Infallible, KVAttributeValue, KeyedAttribute,
@@ -11,8 +21,11 @@ use rstml::{
 	},
 	ParsingResult,
 };
-use std::collections::HashSet;
-use syn::{spanned::Spanned, Expr, ExprLit, Lit, LitBool, Path};
+use std::{collections::HashSet, rc::Rc};
+use syn::{
+	spanned::Spanned, Block, Expr, ExprBlock, ExprLit, ExprParen, ExprRange, Lit, LitBool, LitStr,
+	Path, RangeLimits, Stmt, StmtMacro,
+};
 
 enum TagType {
 	Component(Path),
@@ -21,37 +34,63 @@ enum TagType {
 }
 
 pub struct TemplateParser {
-	empty_elements: &'static HashSet<&'static str>,
+	config: Rc<TemplateConfig>,
+	placeholders: Rc<Placeholders>,
+	in_foreign_content: bool,
+	whitespace: Whitespace,
+	/// Whether statements were written in the current scope.
+	has_statements: bool,
 	instructions: Vec<TemplateWriteInstruction>,
 	diagnostics: Vec<Diagnostic>,
 	ide_helper: IdeHelper,
 }
 
 impl TemplateParser {
-	pub(super) fn new(empty_elements: &'static HashSet<&'static str>) -> Self {
+	pub(super) fn new(config: Rc<TemplateConfig>) -> Self {
 		Self {
-			empty_elements,
+			whitespace: config.whitespace,
+			config,
+			placeholders: Default::default(),
+			in_foreign_content: false,
+			has_statements: false,
 			instructions: Vec::new(),
 			diagnostics: Vec::new(),
 			ide_helper: IdeHelper::new(),
 		}
 	}
 
-	pub fn parse_syn_stream(self, stream: syn::parse::ParseStream) -> Template {
+	pub fn parse_syn_stream(self, stream: syn::parse::ParseStream) -> syn::Result<Template> {
+		let tokens: TokenStream = stream.parse()?;
+		Ok(self.parse_token_stream(tokens))
+	}
+
+	pub fn parse_token_stream(mut self, tokens: TokenStream) -> Template {
+		let tokens = match Rc::make_mut(&mut self.config).parse_header(tokens) {
+			Ok(tokens) => tokens,
+			Err(err) => {
+				self.diagnostics.push(err.into());
+				return self.parse_nodes(None);
+			}
+		};
+
+		self.whitespace = self.config.whitespace;
+
 		let config = rstml::ParserConfig::new()
 			.recover_block(true)
-			.always_self_closed_elements(self.empty_elements.clone())
-			.raw_text_elements(["script", "style"].into_iter().collect());
+			.always_self_closed_elements(self.config.empty_elements.clone())
+			.raw_text_elements(self.config.raw_text_elements.clone());
 
 		let parser = rstml::Parser::new(config);
-		let parsing_result = parser.parse_syn_stream(stream);
+		let (tokens, placeholders) = preprocess(tokens, &self.config.raw_text_elements);
+		self.placeholders = Rc::new(placeholders);
+		let parsing_result = parser.parse_recoverable(tokens);
 
 		self.parse(parsing_result)
 	}
 
 	pub fn parse(mut self, parsing_result: ParsingResult<Vec<Node>>) -> Template {
 		let (nodes, diagnostics) = parsing_result.split();
-		self.diagnostics = diagnostics;
+		self.diagnostics.extend(diagnostics);
 
 		self.parse_nodes(nodes)
 	}
@@ -65,14 +104,34 @@ impl TemplateParser {
 
 		Template {
 			instructions: self.instructions,
+			elements: self.config.changed_elements(),
 			diagnostics: self.diagnostics,
 			ide_helper: self.ide_helper,
 		}
 	}
 
 	fn visit_nodes(&mut self, nodes: impl IntoIterator<Item = Node>) {
-		for node in nodes {
-			self.visit_node(node);
+		let mut nodes = nodes.into_iter().peekable();
+		let mut previous = None;
+		while let Some(node) = nodes.next() {
+			let tokens = node.to_token_stream();
+			match node {
+				Node::RawText(raw_text) => {
+					let gap =
+						|before: Option<&TokenStream>, after: Option<&TokenStream>| match (before, after) {
+							(Some(before), Some(after)) => Gap::between(before, after),
+							_ => Gap::None,
+						};
+
+					let next = nodes.peek().map(Node::to_token_stream);
+					let before = gap(previous.as_ref(), Some(&tokens));
+					let after = gap(Some(&tokens), next.as_ref());
+					self.visit_raw_text(raw_text, before, after);
+				}
+				node => self.visit_node(node),
+			}
+
+			previous = Some(tokens);
 		}
 	}
 
@@ -81,7 +140,7 @@ impl TemplateParser {
 			Node::Doctype(doctype) => self.visit_doctype(doctype),
 			Node::Element(element) => self.visit_element(element),
 			Node::Text(text) => self.visit_text(text),
-			Node::RawText(raw_text) => self.visit_raw_text(raw_text),
+			Node::RawText(raw_text) => self.visit_raw_text(raw_text, Gap::None, Gap::None),
 			Node::Fragment(fragment) => self.visit_fragment(fragment),
 			Node::Comment(comment) => self.visit_comment(comment),
 			Node::Block(block) => self.visit_block(block),
@@ -90,12 +149,13 @@ impl TemplateParser {
 	}
 
 	fn visit_doctype(&mut self, doctype: NodeDoctype) {
+		self.trim_text_end();
 		self
 			.instructions
 			.push(TemplateWriteInstruction::Doctype(doctype.value));
 	}
 
-	fn visit_element(&mut self, element: NodeElement<Infallible>) {
+	fn visit_element(&mut self, mut element: NodeElement<Infallible>) {
 		fn tag_type(name: &NodeName) -> TagType {
 			match name {
 				NodeName::Block(_) => TagType::Block,
@@ -117,6 +177,12 @@ impl TemplateParser {
 			}
 		}
 
+		for attribute in &mut element.open_tag.attributes {
+			if let NodeAttribute::Attribute(KeyedAttribute { key, .. }) = attribute {
+				self.placeholders.restore_attribute_name(key);
+			}
+		}
+
 		match tag_type(element.name()) {
 			TagType::Component(path) => self.visit_component(element, path),
 			TagType::Element => self.visit_html_element(element),
@@ -133,7 +199,10 @@ impl TemplateParser {
 
 			Some(Children::Expr(block))
 		} else {
-			let template = TemplateParser::new(self.empty_elements).parse_nodes(Some(children));
+			let mut parser = TemplateParser::new(self.config.clone());
+			parser.placeholders = self.placeholders.clone();
+			parser.whitespace = self.whitespace;
+			let template = parser.parse_nodes(Some(children));
 
 			if template.is_empty() {
 				None
@@ -162,22 +231,65 @@ impl TemplateParser {
 		};
 
 		let NodeElement { open_tag, .. } = element;
+		self.trim_text_end();
 
+		let mut spread = None;
+		let mut rest = None;
+		let mut attributes = Vec::new();
 		let props = open_tag
 			.attributes
 			.into_iter()
 			.filter_map(|attr| {
-				let NodeAttribute::Attribute(KeyedAttribute {
-					key,
-					possible_value,
-				}) = attr
-				else {
-					self
-						.diagnostics
-						.push(attr.span().error("Only keyed attributes are supported"));
-					return None;
+				let (key, possible_value) = match attr {
+					NodeAttribute::Attribute(KeyedAttribute {
+						key,
+						possible_value,
+					}) => (key, possible_value),
+					NodeAttribute::Block(block) if is_rest(&block) => {
+						if rest.is_some() {
+							self.diagnostics.push(
+								block
+									.span()
+									.error("Components can only have a single `..`."),
+							);
+						}
+
+						rest = Some(block.span());
+						return None;
+					}
+					NodeAttribute::Block(block) => {
+						match spread_expr(&block) {
+							Some(_) if spread.is_some() => self.diagnostics.push(
+								block
+									.span()
+									.error("Components can only have a single spread."),
+							),
+							Some(expr) => spread = Some(Box::new(expr.clone())),
+							None => self.diagnostics.push(
+								block
+									.span()
+									.error("Only keyed attributes and spreads are supported"),
+							),
+						}
+
+						return None;
+					}
 				};
 
+				if let Some(name) = forwarded_attribute_name(&key) {
+					let value = match possible_value {
+						KeyedAttributeValue::Binding(binding) => {
+							self.visit_attribute_binding(&key, binding);
+							return None;
+						}
+						KeyedAttributeValue::Value(value) => Some(self.attribute_value(value)?),
+						KeyedAttributeValue::None => None,
+					};
+
+					attributes.push(ComponentAttribute { name, value });
+					return None;
+				}
+
 				let name = match key {
 					NodeName::Path(path) => {
 						if path.qself.is_some()
@@ -234,7 +346,7 @@ impl TemplateParser {
 							);
 							return None;
 						}
-						KVAttributeValue::Expr(expr) => expr,
+						KVAttributeValue::Expr(expr) => unwrap_value(expr),
 					},
 					KeyedAttributeValue::None => Expr::Lit(ExprLit {
 						attrs: vec![],
@@ -259,11 +371,42 @@ impl TemplateParser {
 			})
 			.collect::<Vec<_>>();
 
+		if !attributes.is_empty() {
+			if let Some(prop) = props.iter().find(|prop| prop.name == "attrs") {
+				self.diagnostics.push(
+					prop
+						.name
+						.span()
+						.error("The `attrs` prop cannot be combined with `attr:` attributes."),
+				);
+			}
+		}
+
+		// struct update syntax requires the spread to have the same type as the component, so
+		// it can't be combined with the closure that writes the forwarded attributes
+		if let (Some(spread), false) = (&spread, attributes.is_empty()) {
+			self.diagnostics.push(spread.span().error(
+				"A spread cannot be combined with `attr:` attributes, set `attrs` in the spread instead.",
+			));
+			attributes.clear();
+		}
+
+		if let (Some(_), Some(rest)) = (&spread, rest) {
+			self.diagnostics.push(
+				rest
+					.error("`..` cannot be combined with a spread, which already provides the other props."),
+			);
+		}
+
+		let builder = rest.is_some() || is_builtin_with_builder(&path);
 		self
 			.instructions
 			.push(TemplateWriteInstruction::Component(Component {
 				path,
 				props,
+				attributes,
+				spread,
+				builder,
 				children,
 			}));
 	}
@@ -291,14 +434,26 @@ impl TemplateParser {
 		}
 
 		let name = open_tag.name;
+		self.validate_element_name(&name);
+		self.trim_text_end();
+
+		// the formatter keeps track of the tags of elements with dynamic attributes, so the
+		// attributes can be merged with the other attributes of the element
+		let has_dynamic_attributes = open_tag
+			.attributes
+			.iter()
+			.any(|attribute| matches!(attribute, NodeAttribute::Block(_)));
 		self
 			.instructions
-			.push(TemplateWriteInstruction::OpenTagStart(name.clone()));
+			.push(TemplateWriteInstruction::OpenTagStart(
+				name.clone(),
+				has_dynamic_attributes,
+			));
 
 		// attributes
 		self.visit_html_attributes(&name, open_tag.attributes);
 
-		if self.empty_elements.contains(&*name.to_string()) {
+		if self.config.empty_elements.contains(&*name.to_string()) {
 			// special empty tags that can't have children (for instance <br>)
 			self
 				.instructions
@@ -313,8 +468,36 @@ impl TemplateParser {
 			// normal tags
 			self.instructions.push(TemplateWriteInstruction::OpenTagEnd);
 
-			// children
-			self.visit_nodes(children);
+			// children, where the content of SVG and MathML elements is foreign
+			let tag = name.to_string();
+			let in_foreign_content = self.in_foreign_content;
+			let whitespace = self.whitespace;
+			let has_statements = std::mem::take(&mut self.has_statements);
+			let children_start = self.instructions.len();
+			self.in_foreign_content |= names::FOREIGN_ELEMENTS.contains(&&*tag);
+			if let Some(mode) = self.config.element_whitespace.get(&tag) {
+				self.whitespace = *mode;
+			}
+
+			if self.config.raw_text_elements.contains(&*tag) {
+				self.visit_raw_text_children(&tag, children);
+			} else {
+				self.visit_nodes(children);
+			}
+
+			self.trim_text_end();
+			self.in_foreign_content = in_foreign_content;
+			self.whitespace = whitespace;
+
+			// bindings created by statements are scoped to the element
+			if self.has_statements {
+				let children = self.instructions.split_off(children_start);
+				self
+					.instructions
+					.push(TemplateWriteInstruction::Scope(children));
+			}
+
+			self.has_statements = has_statements;
 
 			// end tag
 			self
@@ -328,53 +511,270 @@ impl TemplateParser {
 		element_name: &NodeName,
 		attributes: impl IntoIterator<Item = NodeAttribute>,
 	) {
+		let attributes = attributes.into_iter().collect::<Vec<_>>();
+		let keys = attributes
+			.iter()
+			.filter_map(|attribute| match attribute {
+				NodeAttribute::Attribute(KeyedAttribute { key, .. }) => Some(key),
+				NodeAttribute::Block(_) => None,
+			})
+			.collect::<Vec<_>>();
+
+		// `class` and `style` attributes are merged with their duplicates and directives into a
+		// single attribute, written at the position of the first of them
+		let mut merged = MERGED_ATTRIBUTES
+			.iter()
+			.filter(|name| {
+				let keys = keys
+					.iter()
+					.map(|key| attribute_key(key).to_ascii_lowercase())
+					.filter(|key| key.split_once(':').map_or(&**key, |(prefix, _)| prefix) == **name)
+					.collect::<Vec<_>>();
+
+				keys.len() > 1 || keys.iter().any(|key| key.contains(':'))
+			})
+			.map(|name| (*name, None))
+			.collect::<Vec<(&'static str, Option<usize>)>>();
+
+		// browsers ignore all but the first of duplicate attributes
+		let mut seen = HashSet::new();
+		for key in &keys {
+			let name = attribute_key(key).to_ascii_lowercase();
+			if !name.contains(':') && !MERGED_ATTRIBUTES.contains(&&*name) && !seen.insert(name.clone()) {
+				self
+					.diagnostics
+					.push(key.span().error(format!("Duplicate attribute `{name}`.")));
+			}
+		}
+
+		// the attributes of elements with dynamic attributes are written through the formatter,
+		// which merges duplicates and writes them once anything else is written
+		let has_dynamic_attributes = attributes
+			.iter()
+			.any(|attribute| matches!(attribute, NodeAttribute::Block(_)));
 		for attribute in attributes {
-			// TODO: Special handling of class? and duplicates?
-			self.visit_html_attribute(element_name, attribute);
+			let index = match &attribute {
+				NodeAttribute::Attribute(KeyedAttribute { key, .. }) => {
+					let key = attribute_key(key).to_ascii_lowercase();
+					let name = key.split_once(':').map_or(&*key, |(prefix, _)| prefix);
+					merged.iter().position(|(merged, _)| *merged == name)
+				}
+				NodeAttribute::Block(_) => None,
+			};
+
+			match (index, attribute) {
+				(Some(index), NodeAttribute::Attribute(keyed)) => {
+					self.visit_merged_attribute(&mut merged[index], keyed)
+				}
+				(_, NodeAttribute::Attribute(attribute)) => {
+					self.visit_html_static_attribute(element_name, attribute, has_dynamic_attributes)
+				}
+				(_, NodeAttribute::Block(block)) => self.visit_html_block_attribute(element_name, block),
+			}
 		}
 	}
 
-	fn visit_html_attribute(&mut self, element_name: &NodeName, attribute: NodeAttribute) {
-		match attribute {
-			NodeAttribute::Block(block) => self.visit_html_block_attribute(element_name, block),
-			NodeAttribute::Attribute(attribute) => {
-				self.visit_html_static_attribute(element_name, attribute)
+	/// Adds a plain `class` or `style` attribute or a directive to the merged attribute, which
+	/// is written at the position of the first of them. `merged` is the name of the attribute,
+	/// and the index of its instruction once written.
+	fn visit_merged_attribute(
+		&mut self,
+		merged: &mut (&'static str, Option<usize>),
+		keyed: KeyedAttribute,
+	) {
+		let (name, instruction) = merged;
+		let instruction = *instruction.get_or_insert_with(|| {
+			self
+				.instructions
+				.push(TemplateWriteInstruction::MergedAttribute(MergedAttribute {
+					name,
+					span: keyed.key.span(),
+					parts: Vec::new(),
+				}));
+
+			self.instructions.len() - 1
+		});
+
+		if let Some(part) = self.merged_attribute_part(name, keyed) {
+			if let TemplateWriteInstruction::MergedAttribute(attribute) =
+				&mut self.instructions[instruction]
+			{
+				attribute.parts.push(part);
 			}
 		}
 	}
 
 	fn visit_html_block_attribute(&mut self, _element_name: &NodeName, block: NodeBlock) {
+		let block = match spread_expr(&block) {
+			Some(expr) => NodeBlock::ValidBlock(Block {
+				brace_token: Default::default(),
+				stmts: vec![Stmt::Expr(expr.clone(), None)],
+			}),
+			None => block,
+		};
+
 		self
 			.instructions
 			.push(TemplateWriteInstruction::DynamicAttributes(block));
 	}
 
-	fn visit_html_static_attribute(&mut self, element_name: &NodeName, attribute: KeyedAttribute) {
+	fn visit_html_static_attribute(
+		&mut self,
+		element_name: &NodeName,
+		attribute: KeyedAttribute,
+		has_dynamic_attributes: bool,
+	) {
 		let KeyedAttribute {
 			key,
 			possible_value,
 		} = attribute;
 		self.ide_helper.mark_attr_name(key.clone());
+		self.validate_attribute_name(element_name, &key);
 
-		self
-			.instructions
-			.push(TemplateWriteInstruction::AttributeName(key.clone()));
-
-		match possible_value {
+		let value = match possible_value {
 			KeyedAttributeValue::Binding(binding) => {
-				self.visit_attribute_binding(element_name, &key, binding)
+				self.visit_attribute_binding(&key, binding);
+				return;
+			}
+			KeyedAttributeValue::Value(value) => match self.attribute_value(value) {
+				Some(value) => Some(value),
+				None => return,
+			},
+			KeyedAttributeValue::None => None,
+		};
+
+		match value {
+			// dynamic attributes are left out entirely when their value is not present, such as
+			// `None`, and attributes of elements with dynamic attributes are merged with them at
+			// runtime
+			value if has_dynamic_attributes || matches!(value, Some(AttributeValue::Expression(_))) => {
+				self
+					.instructions
+					.push(TemplateWriteInstruction::DynamicAttribute(key, value))
+			}
+			value => {
+				self
+					.instructions
+					.push(TemplateWriteInstruction::AttributeName(key));
+
+				if let Some(value) = value {
+					self
+						.instructions
+						.push(TemplateWriteInstruction::AttributeValue(value));
+				}
 			}
-			KeyedAttributeValue::Value(value) => self.visit_attribute_value(element_name, &key, value),
-			KeyedAttributeValue::None => (),
 		}
 	}
 
-	fn visit_attribute_binding(
+	/// Returns the part of a merged `class` or `style` attribute given by a plain attribute or a
+	/// directive (such as `class:active={cond}`).
+	fn merged_attribute_part(
 		&mut self,
-		_element_name: &NodeName,
-		_attribute_name: &NodeName,
-		binding: FnBinding,
-	) {
+		attribute_name: &str,
+		keyed: KeyedAttribute,
+	) -> Option<MergedPart> {
+		let KeyedAttribute {
+			key,
+			possible_value,
+		} = keyed;
+		self.ide_helper.mark_attr_name(key.clone());
+
+		let name = attribute_key(&key);
+		let directive = name.split_once(':').map(|(_, directive)| directive);
+		let value = match possible_value {
+			KeyedAttributeValue::Binding(binding) => {
+				self.visit_attribute_binding(&key, binding);
+				return None;
+			}
+			KeyedAttributeValue::Value(value) => Some(value),
+			KeyedAttributeValue::None => None,
+		};
+
+		let part = match (directive, value) {
+			(None, Some(value)) => MergedPart::Value(self.attribute_value(value)?),
+			(None, None) => return None,
+			(Some(directive), Some(value)) => match value.value {
+				KVAttributeValue::InvalidBraced(blk) => {
+					self.diagnostics.push(
+						blk
+							.span()
+							.error("Invalid braced expression in attribute value"),
+					);
+					return None;
+				}
+				KVAttributeValue::Expr(expr) => {
+					MergedPart::Directive(directive.to_owned(), Box::new(unwrap_value(expr)))
+				}
+			},
+			(Some(directive), None) if attribute_name == "class" => MergedPart::Directive(
+				directive.to_owned(),
+				Box::new(Expr::Lit(ExprLit {
+					attrs: vec![],
+					lit: Lit::Bool(LitBool {
+						value: true,
+						span: key.span(),
+					}),
+				})),
+			),
+			(Some(_), None) => {
+				self
+					.diagnostics
+					.push(key.span().error("Style directives require a value."));
+				return None;
+			}
+		};
+
+		Some(part)
+	}
+
+	fn validate_element_name(&mut self, name: &NodeName) {
+		if !self.config.validate || self.in_foreign_content || matches!(name, NodeName::Block(_)) {
+			return;
+		}
+
+		let value = name.to_string();
+		if names::is_known_element(&value) || self.config.is_allowed_element(&value) {
+			return;
+		}
+
+		let message = match names::similar_element(&value) {
+			Some(similar) => format!("Unknown element `{}`, did you mean `{}`?", value, similar),
+			None => format!("Unknown element `{}`.", value),
+		};
+
+		self.diagnostics.push(name.span().error(message));
+	}
+
+	fn validate_attribute_name(&mut self, element_name: &NodeName, name: &NodeName) {
+		if !self.config.validate
+			|| self.in_foreign_content
+			|| matches!(element_name, NodeName::Block(_))
+			|| matches!(name, NodeName::Block(_))
+		{
+			return;
+		}
+
+		let element = element_name.to_string();
+		let value = name.to_string();
+		if names::FOREIGN_ELEMENTS.contains(&&*element)
+			|| names::is_known_attribute(&element, &value)
+			|| self.config.is_allowed_attribute(&value)
+		{
+			return;
+		}
+
+		let message = match names::similar_attribute(&element, &value) {
+			Some(similar) => format!(
+				"Unknown attribute `{}` on element `{}`, did you mean `{}`?",
+				value, element, similar
+			),
+			None => format!("Unknown attribute `{}` on element `{}`.", value, element),
+		};
+
+		self.diagnostics.push(name.span().error(message));
+	}
+
+	fn visit_attribute_binding(&mut self, _attribute_name: &NodeName, binding: FnBinding) {
 		self.diagnostics.push(
 			binding
 				.span()
@@ -382,46 +782,130 @@ impl TemplateParser {
 		);
 	}
 
-	fn visit_attribute_value(
-		&mut self,
-		_element_name: &NodeName,
-		_attribute_name: &NodeName,
-		value: AttributeValueExpr,
-	) {
+	fn attribute_value(&mut self, value: AttributeValueExpr) -> Option<AttributeValue> {
 		if let Some(value) = value.value_literal_string() {
-			self
-				.instructions
-				.push(TemplateWriteInstruction::AttributeValue(
-					AttributeValue::Constant(value),
-				));
+			Some(AttributeValue::Constant(value))
 		} else {
-			let expr = match &value.value {
+			let expr = match value.value {
 				KVAttributeValue::InvalidBraced(blk) => {
 					self.diagnostics.push(
 						blk
 							.span()
 							.error("Invalid braced expression in attribute value"),
 					);
-					return;
+					return None;
 				}
-				KVAttributeValue::Expr(expr) => expr,
+				KVAttributeValue::Expr(expr) => unwrap_value(expr),
 			};
-			self
-				.instructions
-				.push(TemplateWriteInstruction::AttributeValue(
-					AttributeValue::Expression(Box::new(expr.clone())),
-				));
+
+			Some(AttributeValue::Expression(Box::new(expr)))
 		}
 	}
 
 	fn visit_text(&mut self, text: NodeText) {
-		self.instructions.push(TemplateWriteInstruction::Text(text));
+		let span = text.span();
+		self.push_text(text.value_string(), span);
 	}
 
-	fn visit_raw_text(&mut self, raw_text: RawText) {
-		self
-			.instructions
-			.push(TemplateWriteInstruction::RawText(raw_text));
+	/// In the preserve mode, unquoted text is written as rstml reads it. In the other modes, it's
+	/// written from its tokens and the whitespace between them in the source.
+	fn visit_raw_text(&mut self, raw_text: RawText, before: Gap, after: Gap) {
+		let span = raw_text.span();
+		let text = match self.whitespace {
+			Whitespace::Preserve => whitespace::preserved_text(&raw_text),
+			mode => whitespace::text_from_tokens(raw_text.to_token_stream(), before, after, mode),
+		};
+
+		self.push_text(text, span);
+	}
+
+	/// The content of raw text elements is written from its source text where available, and is
+	/// not affected by the whitespace mode. Only the content of `script` and `style` is written
+	/// unescaped, the content of other raw text elements such as `textarea` is escaped as text.
+	fn visit_raw_text_children(&mut self, tag: &str, children: Vec<Node>) {
+		for child in children {
+			match child {
+				Node::RawText(raw_text) if matches!(tag, "script" | "style") => self
+					.instructions
+					.push(TemplateWriteInstruction::RawText(raw_text)),
+				Node::RawText(raw_text) => {
+					self
+						.instructions
+						.push(TemplateWriteInstruction::Text(escape::text(
+							&raw_text.to_string_best(),
+						)))
+				}
+				child => self.visit_node(child),
+			}
+		}
+	}
+
+	/// Applies the whitespace mode to text, and escapes it. Character references such as `&copy;`
+	/// are kept, and invalid ones are diagnosed.
+	fn push_text(&mut self, text: String, span: Span) {
+		let (mut text, invalid) = escape::text_with_entities(&self.whitespace.apply(&text));
+		for reference in invalid {
+			let name = &reference[1..reference.len() - 1];
+			let message = match names::similar_entity(name) {
+				Some(similar) => format!(
+					"Unknown character reference `{}`, did you mean `&{};`?",
+					reference, similar
+				),
+				None => format!(
+					"Unknown character reference `{}`, use `&amp;` to write a literal `&`.",
+					reference
+				),
+			};
+
+			self.diagnostics.push(span.error(message));
+		}
+
+		let follows_space = matches!(
+			self.instructions.last(),
+			Some(TemplateWriteInstruction::Text(previous)) if previous.ends_with(char::is_whitespace)
+		);
+
+		if (self.whitespace == Whitespace::Trim && self.follows_tag())
+			|| (self.whitespace != Whitespace::Preserve && follows_space)
+		{
+			text = text.trim_start().to_owned();
+		}
+
+		if !text.is_empty() {
+			self.instructions.push(TemplateWriteInstruction::Text(text));
+		}
+	}
+
+	/// Returns whether the next instruction directly follows a tag, comment or component, or the
+	/// start of the template.
+	fn follows_tag(&self) -> bool {
+		matches!(
+			self.instructions.last(),
+			None
+				| Some(
+					TemplateWriteInstruction::Doctype(_)
+						| TemplateWriteInstruction::OpenTagEnd
+						| TemplateWriteInstruction::SelfCloseTag
+						| TemplateWriteInstruction::EndTag(_)
+						| TemplateWriteInstruction::Comment(_)
+						| TemplateWriteInstruction::Component(_)
+				)
+		)
+	}
+
+	/// Removes trailing whitespace from text that is directly followed by a tag, comment or
+	/// component, when whitespace is trimmed.
+	fn trim_text_end(&mut self) {
+		if self.whitespace != Whitespace::Trim {
+			return;
+		}
+
+		if let Some(TemplateWriteInstruction::Text(text)) = self.instructions.last_mut() {
+			text.truncate(text.trim_end().len());
+			if text.is_empty() {
+				self.instructions.pop();
+			}
+		}
 	}
 
 	fn visit_fragment(&mut self, fragment: NodeFragment<Infallible>) {
@@ -429,14 +913,270 @@ impl TemplateParser {
 	}
 
 	fn visit_comment(&mut self, comment: NodeComment) {
-		self
-			.instructions
-			.push(TemplateWriteInstruction::Comment(comment));
+		self.trim_text_end();
+		if !self.config.strip_comments {
+			self
+				.instructions
+				.push(TemplateWriteInstruction::Comment(comment));
+		}
+	}
+
+	/// Visits a comment with content other than a string literal, such as
+	/// `<!-- rendered by {node_id} in {elapsed:?} -->`. Quoted text is written as is, unquoted
+	/// text is written from its tokens and the whitespace between them in the source, and blocks
+	/// are formatted with `format_args!`, optionally with a format spec.
+	fn visit_dynamic_comment(&mut self, content: TokenStream, span: Span) {
+		self.trim_text_end();
+
+		let mut format = String::new();
+		let mut args = Vec::new();
+		let mut text = Vec::new();
+		let mut previous = None;
+		let mut tokens = content.into_iter().peekable();
+		while let Some(token) = tokens.next() {
+			match (&token, string_literal(&token)) {
+				(_, Some(literal)) => format.push_str(&escape_braces(&literal.value())),
+				(TokenTree::Group(group), _) if group.delimiter() == Delimiter::Brace => {
+					match comment_argument(group) {
+						Ok((arg, spec)) => {
+							format.push_str(&format!("{{{spec}}}"));
+							args.push(arg);
+						}
+						Err(err) => self.diagnostics.push(err.into()),
+					}
+				}
+				_ => {
+					// unquoted text is written from its tokens, like text outside of comments
+					text.push(token.clone());
+					if tokens.peek().is_none_or(is_comment_argument_or_literal) {
+						let before = previous.as_ref().map_or(Gap::None, |previous: &TokenTree| {
+							Gap::between(&previous.clone().into(), &text[0].clone().into())
+						});
+						let after = tokens.peek().map_or(Gap::None, |next| {
+							Gap::between(&token.clone().into(), &next.clone().into())
+						});
+
+						let text = whitespace::text_from_tokens(
+							text.drain(..).collect(),
+							before,
+							after,
+							Whitespace::Preserve,
+						);
+						format.push_str(&escape_braces(&text));
+						previous = Some(token);
+					}
+
+					continue;
+				}
+			}
+
+			previous = Some(token);
+		}
+
+		if self.config.strip_comments {
+			// the values are still referenced, so they aren't reported as unused
+			if !args.is_empty() {
+				self
+					.instructions
+					.push(TemplateWriteInstruction::Statements(vec![
+						syn::parse_quote!(let _ = || (#(&(#args),)*);),
+					]));
+			}
+		} else if args.is_empty() {
+			let content = format.replace("{{", "{").replace("}}", "}");
+			self
+				.instructions
+				.push(TemplateWriteInstruction::Text(format!(
+					"<!--{}-->",
+					escape::text(&content)
+				)));
+		} else {
+			self
+				.instructions
+				.push(TemplateWriteInstruction::DynamicComment(DynamicComment {
+					span,
+					format,
+					args,
+				}));
+		}
 	}
 
 	fn visit_block(&mut self, block: NodeBlock) {
-		self
-			.instructions
-			.push(TemplateWriteInstruction::DynamicContent(block));
+		if let Some(content) = self.placeholders.comment_content(&block) {
+			let content = content.clone();
+			self.visit_dynamic_comment(content, block.span());
+			return;
+		}
+
+		// a block that doesn't end with an expression, such as `{let total = a + b;}`, doesn't
+		// produce any output, and is written as statements so its bindings can be used by the
+		// following nodes
+		match block {
+			NodeBlock::ValidBlock(Block { stmts, .. })
+				if matches!(
+					stmts.last(),
+					Some(Stmt::Local(_) | Stmt::Item(_) | Stmt::Expr(_, Some(_)))
+						| Some(Stmt::Macro(StmtMacro {
+							semi_token: Some(_),
+							..
+						}))
+				) =>
+			{
+				self.has_statements = true;
+				self
+					.instructions
+					.push(TemplateWriteInstruction::Statements(stmts));
+			}
+			block => self
+				.instructions
+				.push(TemplateWriteInstruction::DynamicContent(block)),
+		}
+	}
+}
+
+/// Parses a value of a comment, such as `{node_id}`, or `{elapsed:?}` with a format spec.
+/// Returns the expression and the format spec, starting with `:` if there is one.
+fn comment_argument(group: &Group) -> syn::Result<(Expr, String)> {
+	if let Ok(expr) = syn::parse2::<Expr>(group.stream()) {
+		return Ok((expr, String::new()));
 	}
+
+	// the spec starts at the last `:` that isn't part of a `::`
+	let tokens = group.stream().into_iter().collect::<Vec<_>>();
+	let is_colon =
+		|token: &TokenTree| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ':');
+	let separator = (1..tokens.len()).rev().find(|&i| {
+		is_colon(&tokens[i]) && !is_colon(&tokens[i - 1]) && !tokens.get(i + 1).is_some_and(is_colon)
+	});
+
+	let Some(separator) = separator else {
+		return Err(syn::Error::new(
+			group.span(),
+			"Expected an expression, optionally followed by a format spec such as `{value:?}`.",
+		));
+	};
+
+	let expr = syn::parse2(tokens[..separator].iter().cloned().collect())?;
+	let spec = tokens[separator..]
+		.iter()
+		.map(ToString::to_string)
+		.collect::<String>();
+
+	Ok((expr, spec))
+}
+
+fn is_comment_argument_or_literal(token: &TokenTree) -> bool {
+	match token {
+		TokenTree::Group(group) => group.delimiter() == Delimiter::Brace,
+		token => string_literal(token).is_some(),
+	}
+}
+
+fn string_literal(token: &TokenTree) -> Option<LitStr> {
+	match token {
+		TokenTree::Literal(_) => syn::parse2(token.clone().into()).ok(),
+		_ => None,
+	}
+}
+
+fn escape_braces(text: &str) -> String {
+	text.replace('{', "{{").replace('}', "}}")
+}
+
+/// Attributes that are merged with their directives, such as `class:active={cond}`.
+const MERGED_ATTRIBUTES: &[&str] = &["class", "style"];
+
+/// Returns the name of an attribute, without the whitespace added by tokenization.
+fn attribute_key(key: &NodeName) -> String {
+	let mut name = key.to_string();
+	name.retain(|c| !c.is_ascii_whitespace());
+	name
+}
+
+/// Removes redundant parentheses or braces around an attribute value, such as the ones added
+/// by the preprocessor to separate a value from a following spread, or the braces of
+/// `attr={value}`.
+fn unwrap_value(expr: Expr) -> Expr {
+	match expr {
+		Expr::Paren(ExprParen { expr, .. }) => *expr,
+		Expr::Block(ExprBlock {
+			attrs,
+			label: None,
+			block: Block { mut stmts, .. },
+		}) if attrs.is_empty() && matches!(&*stmts, [Stmt::Expr(_, None)]) => match stmts.pop() {
+			Some(Stmt::Expr(expr, None)) => expr,
+			_ => unreachable!(),
+		},
+		expr => expr,
+	}
+}
+
+/// Whether the block is of the form `{..}`, which constructs a component through its builder.
+fn is_rest(block: &NodeBlock) -> bool {
+	match block {
+		NodeBlock::ValidBlock(Block { stmts, .. }) if stmts.len() == 1 => matches!(
+			&stmts[0],
+			Stmt::Expr(
+				Expr::Range(ExprRange {
+					start: None,
+					limits: RangeLimits::HalfOpen(_),
+					end: None,
+					..
+				}),
+				None,
+			)
+		),
+		_ => false,
+	}
+}
+
+/// Whether the component is one of the built-in components with optional props, which are
+/// always constructed through their builders.
+fn is_builtin_with_builder(path: &Path) -> bool {
+	const BUILTINS: &[&str] = &["ErrorBoundary", "For", "Show", "Suspense", "Switch"];
+
+	let mut segments = path.segments.iter();
+	let name = match (segments.next(), segments.next(), segments.next()) {
+		(Some(name), None, _) if path.leading_colon.is_none() => name,
+		(Some(krate), Some(name), None) if krate.ident == "rstml_component" => name,
+		_ => return false,
+	};
+
+	name.arguments.is_none() && BUILTINS.iter().any(|builtin| name.ident == builtin)
+}
+
+/// Returns the spread expression if the block is of the form `{..expr}`.
+fn spread_expr(block: &NodeBlock) -> Option<&Expr> {
+	match block {
+		NodeBlock::ValidBlock(Block { stmts, .. }) if stmts.len() == 1 => match &stmts[0] {
+			Stmt::Expr(
+				Expr::Range(ExprRange {
+					start: None,
+					limits: RangeLimits::HalfOpen(_),
+					end: Some(expr),
+					..
+				}),
+				None,
+			) => Some(expr),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+/// Returns the attribute name if the component attribute is of the form `attr:name`, which
+/// forwards `name` as a plain HTML attribute through the component's `attrs` prop.
+fn forwarded_attribute_name(key: &NodeName) -> Option<String> {
+	let NodeName::Punctuated(name) = key else {
+		return None;
+	};
+
+	let first = name.pairs().next()?;
+	if first.value().to_string() != "attr" || first.punct()?.as_char() != ':' {
+		return None;
+	}
+
+	let mut name = key.to_string();
+	name.retain(|c| !c.is_ascii_whitespace());
+	Some(name["attr:".len()..].to_owned())
 }
@@ -0,0 +1,485 @@
+//! Rewrites template syntax that rstml is unable to parse into equivalent forms it does
+//! understand, before the token stream is handed to the rstml parser.
+//!
+//! Only the attribute lists of open tags are touched:
+//!
+//! - `..expr` (a spread) is rewritten to the block attribute `{..expr}`, and a `..` without an
+//!   expression to `{..}`.
+//! - Attribute names starting with `@` or `:` (such as `@click.outside` or `:class`) and quoted
+//!   attribute names (such as `"@keydown.ctrl.k"`) are replaced by placeholder identifiers.
+//! - Comments with content other than a single string literal (such as
+//!   `<!-- rendered by {node_id} -->`) are replaced by placeholder blocks.
+//!
+//! The replaced syntax is restored through [Placeholders] when the template is parsed.
+
+use proc_macro2::{Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream, TokenTree};
+use rstml::node::{NodeBlock, NodeName, NodeNameFragment};
+use std::collections::HashSet;
+use syn::{parse::Parser, punctuated::Punctuated, Block, Expr, LitInt, LitStr, Stmt};
+
+/// Syntax that rstml is unable to parse, indexed by the number of its placeholder.
+#[derive(Default)]
+pub struct Placeholders {
+	attribute_names: Vec<NodeName>,
+	comments: Vec<TokenStream>,
+}
+
+impl Placeholders {
+	const ATTRIBUTE_PREFIX: &'static str = "__rstml_attribute_";
+	const COMMENT_PREFIX: &'static str = "__rstml_comment_";
+
+	fn attribute_name(&mut self, name: NodeName, span: Span) -> TokenTree {
+		let ident = format!("{}{}", Self::ATTRIBUTE_PREFIX, self.attribute_names.len());
+		self.attribute_names.push(name);
+		TokenTree::Ident(Ident::new(&ident, span))
+	}
+
+	fn comment(&mut self, content: TokenStream, span: Span) -> TokenTree {
+		let ident = format!("{}{}", Self::COMMENT_PREFIX, self.comments.len());
+		self.comments.push(content);
+		let mut group = Group::new(
+			Delimiter::Brace,
+			TokenTree::Ident(Ident::new(&ident, span)).into(),
+		);
+		group.set_span(span);
+		TokenTree::Group(group)
+	}
+
+	/// Replaces a placeholder attribute name by the name it was created for.
+	pub fn restore_attribute_name(&self, name: &mut NodeName) {
+		let restored = match name {
+			NodeName::Path(path) => path
+				.path
+				.get_ident()
+				.and_then(|ident| placeholder_index(ident, Self::ATTRIBUTE_PREFIX))
+				.and_then(|index| self.attribute_names.get(index)),
+			_ => None,
+		};
+
+		if let Some(restored) = restored {
+			*name = restored.clone();
+		}
+	}
+
+	/// Returns the content of the comment a placeholder block was created for.
+	pub fn comment_content(&self, block: &NodeBlock) -> Option<&TokenStream> {
+		let NodeBlock::ValidBlock(Block { stmts, .. }) = block else {
+			return None;
+		};
+
+		match &stmts[..] {
+			[Stmt::Expr(Expr::Path(path), None)] => path
+				.path
+				.get_ident()
+				.and_then(|ident| placeholder_index(ident, Self::COMMENT_PREFIX))
+				.and_then(|index| self.comments.get(index)),
+			_ => None,
+		}
+	}
+}
+
+fn placeholder_index(ident: &Ident, prefix: &str) -> Option<usize> {
+	ident.to_string().strip_prefix(prefix)?.parse().ok()
+}
+
+pub fn preprocess(
+	tokens: TokenStream,
+	raw_text_elements: &HashSet<&'static str>,
+) -> (TokenStream, Placeholders) {
+	let tokens = tokens.into_iter().collect::<Vec<_>>();
+	let mut placeholders = Placeholders::default();
+	let mut output = Vec::with_capacity(tokens.len());
+	let mut pos = 0;
+
+	while pos < tokens.len() {
+		if let Some((content, end)) = dynamic_comment(&tokens, pos) {
+			output.push(placeholders.comment(content, tokens[pos].span()));
+			pos = end;
+			continue;
+		}
+
+		if !is_open_tag_start(&tokens, pos) {
+			output.push(tokens[pos].clone());
+			pos += 1;
+			continue;
+		}
+
+		let raw_text_tag = match &tokens[pos + 1] {
+			TokenTree::Ident(ident) if !is_punct(tokens.get(pos + 2), &['-', ':']) => {
+				let name = ident.to_string();
+				raw_text_elements.contains(&*name).then_some(name)
+			}
+			_ => None,
+		};
+
+		output.push(tokens[pos].clone());
+		pos += 1;
+
+		let self_closed;
+		(pos, self_closed) = rewrite_open_tag(&tokens, pos, &mut output, &mut placeholders);
+
+		if let (Some(name), false) = (raw_text_tag, self_closed) {
+			// raw text content is passed on verbatim up to the matching close tag
+			while pos < tokens.len() && !is_close_tag(&tokens, pos, &name) {
+				output.push(tokens[pos].clone());
+				pos += 1;
+			}
+		}
+	}
+
+	(output.into_iter().collect(), placeholders)
+}
+
+/// Copies the open tag starting at `pos` (just after the `<`) to `output`, rewriting its
+/// attributes. Returns the position after the tag and whether the tag was self-closed.
+fn rewrite_open_tag(
+	tokens: &[TokenTree],
+	mut pos: usize,
+	output: &mut Vec<TokenTree>,
+	placeholders: &mut Placeholders,
+) -> (usize, bool) {
+	let tag_start = output.len();
+	while pos < tokens.len() {
+		let token = &tokens[pos];
+
+		if is_punct(Some(token), &['>']) {
+			output.push(token.clone());
+			return (pos + 1, false);
+		}
+
+		if is_punct(Some(token), &['/']) && is_punct(tokens.get(pos + 1), &['>']) {
+			output.extend_from_slice(&tokens[pos..pos + 2]);
+			return (pos + 2, true);
+		}
+
+		if is_spread_start(tokens, pos) {
+			let expr_end = spread_expr_end(tokens, pos + 2);
+			if expr_end > pos + 2 {
+				parenthesize_trailing_value(output, tag_start);
+				let mut group = Group::new(
+					Delimiter::Brace,
+					tokens[pos..expr_end].iter().cloned().collect(),
+				);
+				group.set_span(token.span());
+				output.push(TokenTree::Group(group));
+				pos = expr_end;
+				continue;
+			}
+
+			if is_tag_end(tokens, pos + 2) {
+				parenthesize_trailing_value(output, tag_start);
+				let mut group = Group::new(
+					Delimiter::Brace,
+					tokens[pos..pos + 2].iter().cloned().collect(),
+				);
+				group.set_span(token.span());
+				output.push(TokenTree::Group(group));
+				pos += 2;
+				continue;
+			}
+		}
+
+		if let Some((name, end)) = unsupported_attribute_name(tokens, pos) {
+			output.push(placeholders.attribute_name(name, token.span()));
+			pos = end;
+			continue;
+		}
+
+		output.push(token.clone());
+		pos += 1;
+	}
+
+	(pos, false)
+}
+
+/// Parses an attribute name at `pos` that rstml is unable to parse, returning the name and the
+/// position after it.
+///
+/// Names starting with `@` or `:` must follow another attribute or the tag name, and are made of
+/// identifiers and numbers separated by `-`, `:` or `.`, like the names rstml parses. Punctuation
+/// that is joined to the next punctuation (as in `::`) isn't part of a name.
+///
+/// Like rstml, a `:` directly after the name of an attribute is part of that name, as in
+/// `attr:disabled`, so a name starting with `:` must follow the tag name or the value of an
+/// attribute. It must also be followed by `=`, so a type annotation (as in `|value: u32|`) isn't
+/// mistaken for a name. A string literal is a quoted name if it follows another attribute or
+/// the tag name.
+fn unsupported_attribute_name(tokens: &[TokenTree], pos: usize) -> Option<(NodeName, usize)> {
+	let previous = tokens.get(pos.checked_sub(1)?)?;
+	if matches!(previous, TokenTree::Punct(_)) {
+		return None;
+	}
+
+	let token = &tokens[pos];
+	match token {
+		TokenTree::Punct(punct) if matches!(punct.as_char(), '@' | ':') => {
+			if punct.spacing() == Spacing::Joint
+				|| (punct.as_char() == ':' && follows_attribute_name(tokens, pos))
+			{
+				return None;
+			}
+
+			let mut name = Punctuated::new();
+			name.push_value(NodeNameFragment::Empty);
+			name.push_punct(punct.clone());
+
+			let mut end = pos + 1;
+			while let Some(fragment) = tokens.get(end).and_then(name_fragment) {
+				name.push_value(fragment);
+				end += 1;
+				match tokens.get(end) {
+					Some(TokenTree::Punct(punct))
+						if matches!(punct.as_char(), '-' | ':' | '.') && punct.spacing() == Spacing::Alone =>
+					{
+						name.push_punct(punct.clone());
+						end += 1;
+					}
+					_ => break,
+				}
+			}
+
+			if name.trailing_punct() || (punct.as_char() == ':' && !is_punct(tokens.get(end), &['='])) {
+				return None;
+			}
+
+			Some((NodeName::Punctuated(name), end))
+		}
+		TokenTree::Literal(literal) => {
+			let name = syn::parse2::<LitStr>(token.clone().into()).ok()?;
+			Some((quoted_name(&name.value(), literal.span())?, pos + 1))
+		}
+		_ => None,
+	}
+}
+
+/// Returns whether the tokens before `pos` end with a name that isn't the tag name or part of an
+/// attribute value, which is the name of an attribute.
+fn follows_attribute_name(tokens: &[TokenTree], pos: usize) -> bool {
+	let mut start = pos;
+	while start > 0 && name_fragment(&tokens[start - 1]).is_some() {
+		start -= 1;
+		match start.checked_sub(2) {
+			Some(separator)
+				if is_punct(tokens.get(separator + 1), &['-', ':', '.'])
+					&& name_fragment(&tokens[separator]).is_some() =>
+			{
+				start = separator + 1;
+			}
+			_ => break,
+		}
+	}
+
+	start < pos
+		&& !is_punct(
+			start.checked_sub(1).and_then(|i| tokens.get(i)),
+			&['<', '='],
+		)
+}
+
+/// Splits a quoted attribute name into a name rstml would produce, with identifiers and
+/// numbers separated by punctuation.
+fn quoted_name(value: &str, span: Span) -> Option<NodeName> {
+	const PUNCTUATION: &str = "!#$%&*+,-.:;<?@^|~";
+
+	let mut name = Punctuated::new();
+	let mut rest = value;
+	while !rest.is_empty() {
+		let end = rest
+			.find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+			.unwrap_or(rest.len());
+
+		if end > 0 {
+			name.push_value(name_fragment(&syn::parse_str(&rest[..end]).ok()?)?);
+		} else {
+			name.push_value(NodeNameFragment::Empty);
+		}
+
+		rest = &rest[end..];
+		let Some(ch) = rest.chars().next() else {
+			break;
+		};
+
+		if !PUNCTUATION.contains(ch) {
+			return None;
+		}
+
+		name.push_punct(Punct::new(ch, Spacing::Alone));
+		rest = &rest[1..];
+	}
+
+	let mut name = NodeName::Punctuated(name);
+	set_span(&mut name, span);
+	(!value.is_empty()).then_some(name)
+}
+
+fn name_fragment(token: &TokenTree) -> Option<NodeNameFragment> {
+	match token {
+		TokenTree::Ident(ident) => Some(NodeNameFragment::Ident(ident.clone())),
+		TokenTree::Literal(_) => syn::parse2::<LitInt>(token.clone().into())
+			.ok()
+			.map(NodeNameFragment::Literal),
+		_ => None,
+	}
+}
+
+fn set_span(name: &mut NodeName, span: Span) {
+	let NodeName::Punctuated(name) = name else {
+		return;
+	};
+
+	for mut pair in name.pairs_mut() {
+		match pair.value_mut() {
+			NodeNameFragment::Ident(ident) => ident.set_span(span),
+			NodeNameFragment::Literal(literal) => literal.set_span(span),
+			NodeNameFragment::Empty => (),
+		}
+
+		if let Some(punct) = pair.punct_mut() {
+			punct.set_span(span);
+		}
+	}
+}
+
+/// Wraps the value of the last attribute written to `output` in parentheses, unless
+/// it is a literal or already delimited. Otherwise a value such as `label=label` followed by
+/// the rewritten `{..expr}` would be parsed as the struct literal `label {..expr}`.
+fn parenthesize_trailing_value(output: &mut Vec<TokenTree>, tag_start: usize) {
+	let Some(eq) = (tag_start..output.len()).rev().find(|&i| {
+		matches!(&output[i], TokenTree::Punct(punct) if punct.as_char() == '=' && punct.spacing() == Spacing::Alone)
+			&& !matches!(&output[i - 1], TokenTree::Punct(punct) if punct.spacing() == Spacing::Joint)
+	}) else {
+		return;
+	};
+
+	let value = output[eq + 1..].iter().cloned().collect::<TokenStream>();
+	let parser = |input: syn::parse::ParseStream| {
+		input.parse::<Expr>()?;
+		let rest: TokenStream = input.parse()?;
+		Ok(rest.into_iter().count())
+	};
+
+	let Ok(rest_len) = parser.parse2(value) else {
+		return;
+	};
+
+	let value_end = output.len() - rest_len;
+	let ends_in_path = matches!(output.get(value_end - 1), Some(TokenTree::Ident(_)))
+		&& !is_punct(output.get(value_end.wrapping_sub(2)), &['.']);
+	if value_end <= eq + 1 || !ends_in_path {
+		return;
+	}
+
+	let value = output
+		.splice(eq + 1..value_end, [])
+		.collect::<TokenStream>();
+	let mut group = Group::new(Delimiter::Parenthesis, value);
+	group.set_span(output[eq].span());
+	output.insert(eq + 1, TokenTree::Group(group));
+}
+
+/// Returns the content of the comment starting at `pos` and the position after it, unless the
+/// comment consists of a single string literal, which rstml parses itself.
+fn dynamic_comment(tokens: &[TokenTree], pos: usize) -> Option<(TokenStream, usize)> {
+	let is_sequence = |pos: usize, chars: &str| {
+		chars
+			.chars()
+			.enumerate()
+			.all(|(i, ch)| is_punct(tokens.get(pos + i), &[ch]))
+	};
+
+	if !is_sequence(pos, "<!--") {
+		return None;
+	}
+
+	let start = pos + 4;
+	let end = (start..tokens.len()).find(|&pos| is_sequence(pos, "-->"))?;
+	let content = &tokens[start..end];
+	if let [literal @ TokenTree::Literal(_)] = content {
+		if syn::parse2::<LitStr>(literal.clone().into()).is_ok() {
+			return None;
+		}
+	}
+
+	Some((content.iter().cloned().collect(), end + 3))
+}
+
+fn is_punct(token: Option<&TokenTree>, chars: &[char]) -> bool {
+	matches!(token, Some(TokenTree::Punct(punct)) if chars.contains(&punct.as_char()))
+}
+
+fn is_open_tag_start(tokens: &[TokenTree], pos: usize) -> bool {
+	is_punct(tokens.get(pos), &['<'])
+		&& matches!(
+			tokens.get(pos + 1),
+			Some(TokenTree::Ident(_)) | Some(TokenTree::Group(_))
+		)
+}
+
+fn is_close_tag(tokens: &[TokenTree], pos: usize, name: &str) -> bool {
+	is_punct(tokens.get(pos), &['<'])
+		&& is_punct(tokens.get(pos + 1), &['/'])
+		&& matches!(tokens.get(pos + 2), Some(TokenTree::Ident(ident)) if ident == name)
+}
+
+/// A spread is `..` at the start of an attribute, i.e. not following an `=` (where it
+/// would be part of a range expression) and not part of a `...` or `..=`.
+fn is_spread_start(tokens: &[TokenTree], pos: usize) -> bool {
+	let (Some(TokenTree::Punct(first)), Some(TokenTree::Punct(second))) =
+		(tokens.get(pos), tokens.get(pos + 1))
+	else {
+		return false;
+	};
+
+	first.as_char() == '.'
+		&& first.spacing() == Spacing::Joint
+		&& second.as_char() == '.'
+		&& (second.spacing() == Spacing::Alone || is_tag_end(tokens, pos + 2))
+		&& !(pos > 0 && is_punct(tokens.get(pos - 1), &['=']))
+}
+
+/// Whether the open tag ends at `pos`, with `>` or `/>`.
+fn is_tag_end(tokens: &[TokenTree], pos: usize) -> bool {
+	is_punct(tokens.get(pos), &['>'])
+		|| (is_punct(tokens.get(pos), &['/']) && is_punct(tokens.get(pos + 1), &['>']))
+}
+
+/// Finds the end of the expression following a spread. Only simple expressions are
+/// supported without braces: paths, field accesses, method calls and indexing, such as
+/// `..self.props.clone()` or `..Default::default()`.
+fn spread_expr_end(tokens: &[TokenTree], start: usize) -> usize {
+	let mut pos = start;
+	match tokens.get(pos) {
+		Some(TokenTree::Ident(_)) | Some(TokenTree::Literal(_)) => pos += 1,
+		Some(TokenTree::Group(group)) if group.delimiter() != Delimiter::None => pos += 1,
+		_ => return start,
+	}
+
+	loop {
+		match (tokens.get(pos), tokens.get(pos + 1), tokens.get(pos + 2)) {
+			(Some(TokenTree::Punct(dot)), Some(TokenTree::Ident(_) | TokenTree::Literal(_)), _)
+				if dot.as_char() == '.' && dot.spacing() == Spacing::Alone =>
+			{
+				pos += 2
+			}
+			(
+				Some(TokenTree::Punct(first)),
+				Some(TokenTree::Punct(second)),
+				Some(TokenTree::Ident(_)),
+			) if first.as_char() == ':'
+				&& first.spacing() == Spacing::Joint
+				&& second.as_char() == ':' =>
+			{
+				pos += 3
+			}
+			(Some(TokenTree::Group(group)), _, _)
+				if matches!(
+					group.delimiter(),
+					Delimiter::Parenthesis | Delimiter::Bracket
+				) =>
+			{
+				pos += 1
+			}
+			_ => return pos,
+		}
+	}
+}
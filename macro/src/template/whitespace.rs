@@ -0,0 +1,259 @@
+//! Handling of whitespace in template text.
+//!
+//! In the preserve mode, unquoted text is written as rstml reads it. In the other modes, it's
+//! written from its tokens, with a single space wherever tokens are separated by whitespace in
+//! the source. Where unquoted text is separated by whitespace from a neighbouring node of the
+//! same parent, such as the block in `Hello {name}`, a single space is written at that end of the
+//! text. Character references such as `&copy;` are written as is in all modes, and so is quoted
+//! text. The whitespace mode of the template then determines how the whitespace in the resulting
+//! text is handled.
+
+use proc_macro2::{Delimiter, Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use rstml::node::RawText;
+use syn::LitStr;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Whitespace {
+	/// Text is written as is.
+	#[default]
+	Preserve,
+
+	/// Runs of whitespace are collapsed into a single space.
+	Collapse,
+
+	/// Runs of whitespace are collapsed into a single space, and whitespace at the start or end
+	/// of text that is adjacent to a tag is removed.
+	Trim,
+
+	/// Like JSX: in text spanning multiple lines, each line is trimmed, lines that only contain
+	/// whitespace are removed, and the remaining lines are joined with a single space.
+	Jsx,
+}
+
+impl Whitespace {
+	pub fn parse(value: &LitStr) -> syn::Result<Self> {
+		match &*value.value() {
+			"preserve" => Ok(Self::Preserve),
+			"collapse" => Ok(Self::Collapse),
+			"trim" => Ok(Self::Trim),
+			"jsx" => Ok(Self::Jsx),
+			_ => Err(syn::Error::new(
+				value.span(),
+				"unknown whitespace mode, expected `preserve`, `collapse`, `trim` or `jsx`",
+			)),
+		}
+	}
+
+	/// Applies the whitespace mode to text. Whitespace adjacent to tags is removed separately,
+	/// by the parser.
+	pub fn apply(self, text: &str) -> String {
+		match self {
+			Whitespace::Preserve => text.to_owned(),
+			Whitespace::Collapse | Whitespace::Trim => collapse(text),
+			Whitespace::Jsx => jsx(text),
+		}
+	}
+}
+
+fn collapse(text: &str) -> String {
+	let mut collapsed = String::with_capacity(text.len());
+	let mut in_whitespace = false;
+	for ch in text.chars() {
+		if ch.is_whitespace() {
+			if !in_whitespace {
+				collapsed.push(' ');
+			}
+			in_whitespace = true;
+		} else {
+			collapsed.push(ch);
+			in_whitespace = false;
+		}
+	}
+
+	collapsed
+}
+
+fn jsx(text: &str) -> String {
+	if !text.contains('\n') {
+		return text.to_owned();
+	}
+
+	text
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty())
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+/// The whitespace between two nodes or tokens in the source.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Gap {
+	/// The nodes are adjacent, or there is no neighbouring node.
+	None,
+
+	/// The nodes are separated by whitespace on the same line.
+	Space,
+
+	/// The nodes are separated by whitespace containing a line break.
+	Line,
+}
+
+impl Gap {
+	/// Returns the gap between the end of the tokens `before` and the start of `after`.
+	pub fn between(before: &TokenStream, after: &TokenStream) -> Self {
+		match (token_range(before), token_range(after)) {
+			(Some((_, end)), Some((start, _))) => Self::between_spans(end, start),
+			_ => Gap::None,
+		}
+	}
+
+	/// Returns the gap between the end of the span `before` and the start of `after`. Token
+	/// locations are available from Rust 1.88, older compilers report line 0, where there is
+	/// no gap.
+	fn between_spans(before: Span, after: Span) -> Self {
+		let (end, start) = (before.end(), after.start());
+		if end.line == 0 || start.line == 0 {
+			Gap::None
+		} else if end.line != start.line {
+			Gap::Line
+		} else if end.column < start.column {
+			Gap::Space
+		} else {
+			Gap::None
+		}
+	}
+}
+
+fn token_range(tokens: &TokenStream) -> Option<(Span, Span)> {
+	let mut tokens = tokens.clone().into_iter();
+	let first = tokens.next()?.span();
+	let last = tokens.last().map_or(first, |token| token.span());
+	Some((first, last))
+}
+
+/// Writes unquoted text as rstml reads it, for the preserve mode. Character references such as
+/// `&copy;` are kept as they are written.
+pub fn preserved_text(raw_text: &RawText) -> String {
+	let tokens = raw_text.to_token_stream().into_iter().collect::<Vec<_>>();
+	if !(0..tokens.len()).any(|index| character_reference(&tokens[index..]).is_some()) {
+		return raw_text.to_string_best();
+	}
+
+	let mut parts = Vec::new();
+	let mut run = TokenStream::new();
+	let mut index = 0;
+	while index < tokens.len() {
+		if let Some((reference, len)) = character_reference(&tokens[index..]) {
+			if !run.is_empty() {
+				parts.push(std::mem::take(&mut run).to_string());
+			}
+
+			parts.push(reference);
+			index += len;
+		} else {
+			run.extend([tokens[index].clone()]);
+			index += 1;
+		}
+	}
+
+	if !run.is_empty() {
+		parts.push(run.to_string());
+	}
+
+	parts.join(" ")
+}
+
+/// Writes unquoted text from its tokens, with a single space where tokens are separated by
+/// whitespace in the source, and at the start or end where the text is separated from its
+/// neighbours. In the JSX mode, whitespace containing a line break at the start or end is
+/// removed instead.
+pub fn text_from_tokens(tokens: TokenStream, before: Gap, after: Gap, mode: Whitespace) -> String {
+	let space = |gap| match gap {
+		Gap::None => false,
+		Gap::Space => true,
+		Gap::Line => mode != Whitespace::Jsx,
+	};
+
+	let mut text = String::new();
+	if space(before) {
+		text.push(' ');
+	}
+
+	write_tokens(&mut text, tokens, &mut None);
+	if space(after) {
+		text.push(' ');
+	}
+
+	text
+}
+
+/// Writes the tokens to `text`. `previous` is the span of the previously written token, which
+/// is separated from the next token by a space if there is whitespace between them.
+fn write_tokens(text: &mut String, tokens: TokenStream, previous: &mut Option<Span>) {
+	let tokens = tokens.into_iter().collect::<Vec<_>>();
+	let mut index = 0;
+	while index < tokens.len() {
+		if let Some((reference, len)) = character_reference(&tokens[index..]) {
+			write_token(text, &reference, tokens[index].span(), previous);
+			*previous = Some(tokens[index + len - 1].span());
+			index += len;
+			continue;
+		}
+
+		let token = tokens[index].clone();
+		index += 1;
+		match token {
+			TokenTree::Group(group) if group.delimiter() == Delimiter::None => {
+				write_tokens(text, group.stream(), previous);
+			}
+
+			TokenTree::Group(group) => {
+				let (open, close) = match group.delimiter() {
+					Delimiter::Parenthesis => ("(", ")"),
+					Delimiter::Brace => ("{", "}"),
+					Delimiter::Bracket => ("[", "]"),
+					Delimiter::None => unreachable!(),
+				};
+
+				write_token(text, open, group.span_open(), previous);
+				write_tokens(text, group.stream(), previous);
+				write_token(text, close, group.span_close(), previous);
+			}
+
+			token => write_token(text, &token.to_string(), token.span(), previous),
+		}
+	}
+}
+
+/// Returns the character reference at the start of the tokens, such as `& copy ;`, `& # 169 ;`
+/// or `& # xA9 ;`, and the number of tokens it spans.
+fn character_reference(tokens: &[TokenTree]) -> Option<(String, usize)> {
+	let is_punct =
+		|token: &TokenTree, ch| matches!(token, TokenTree::Punct(punct) if punct.as_char() == ch);
+
+	match tokens {
+		[amp, TokenTree::Ident(name), semi, ..] if is_punct(amp, '&') && is_punct(semi, ';') => {
+			Some((format!("&{};", name), 3))
+		}
+		[amp, hash, number, semi, ..]
+			if is_punct(amp, '&')
+				&& is_punct(hash, '#')
+				&& matches!(number, TokenTree::Ident(_) | TokenTree::Literal(_))
+				&& is_punct(semi, ';') =>
+		{
+			Some((format!("&#{};", number), 4))
+		}
+		_ => None,
+	}
+}
+
+fn write_token(text: &mut String, token: &str, span: Span, previous: &mut Option<Span>) {
+	if previous.is_some_and(|previous| Gap::between_spans(previous, span) != Gap::None) {
+		text.push(' ');
+	}
+
+	text.push_str(token);
+	*previous = Some(span);
+}
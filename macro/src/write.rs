@@ -79,10 +79,10 @@ impl ToTokens for HtmlExpr {
 		let move_token = self.should_move.then(|| quote!(move));
 
 		tokens.extend(quote! {
-			#move_token |#formatter: &mut ::rstml_component::HtmlFormatter| -> ::std::fmt::Result {
+			(#move_token |#formatter: &mut ::rstml_component::HtmlFormatter| -> ::std::fmt::Result {
 				#template
 				Ok(())
-			}
+			})
 		})
 	}
 }
@@ -96,3 +96,51 @@ pub fn html(input: TokenStream, should_move: bool) -> TokenStream {
 	template.should_move = should_move;
 	template.into_token_stream()
 }
+
+/// A fully static template, which is expanded to a `StaticHtml` constant.
+struct StaticHtmlExpr {
+	template: Template,
+}
+
+impl Parse for StaticHtmlExpr {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		Ok(Self {
+			template: input.parse()?,
+		})
+	}
+}
+
+impl ToTokens for StaticHtmlExpr {
+	fn to_tokens(&self, tokens: &mut TokenStream) {
+		let diagnostics = self.template.diagnostics();
+		let output = match self.template.static_output() {
+			Ok(output) => output,
+			Err(span) => {
+				let error = syn::Error::new(
+					span,
+					"`static_html!` templates can't contain dynamic values or components, use `html!` instead",
+				)
+				.into_compile_error();
+
+				tokens.extend(quote!({ #diagnostics #error }));
+				return;
+			}
+		};
+
+		tokens.extend(quote! {
+			{
+				#diagnostics
+				::rstml_component::StaticHtml::from_raw(#output)
+			}
+		})
+	}
+}
+
+pub fn static_html(input: TokenStream) -> TokenStream {
+	let expr: StaticHtmlExpr = match syn::parse2(input) {
+		Ok(expr) => expr,
+		Err(err) => return err.to_compile_error(),
+	};
+
+	expr.into_token_stream()
+}
@@ -0,0 +1,357 @@
+use crate::{
+	suspense::{PendingContent, ResolvedContent},
+	HtmlContent, HtmlFormatter, SuspenseQueue,
+};
+use bytes::{Bytes, BytesMut};
+use std::{
+	collections::VecDeque,
+	fmt,
+	future::{poll_fn, Future},
+	task::{Context, Poll},
+};
+
+/// A trait representing content that has to be resolved asynchronously before it can be
+/// formatted, such as the content of an `async` [component](crate::component).
+///
+/// It's implemented for futures that resolve to [HtmlContent]. Async content is written in
+/// templates through [HtmlFormatter::write_async_content], which defers it until it's resolved
+/// by an [AsyncRender].
+///
+/// ```
+/// # use rstml_component::{component, html, AsyncHtmlContent, HtmlContent};
+/// async fn load_user(id: u32) -> String {
+///     format!("user {id}")
+/// }
+///
+/// #[component(UserCard)]
+/// async fn user_card(id: u32) -> impl HtmlContent {
+///     let user = load_user(id).await;
+///     html!(<div class="user">{user}</div>)
+/// }
+///
+/// # async fn render() {
+/// let page = async {
+///     html!(
+///         <main>
+///             <UserCard id={1} />
+///             <UserCard id={2} />
+///         </main>
+///     )
+/// };
+///
+/// assert_eq!(
+///     page.into_string_async().await.unwrap(),
+///     r#"<main><div class="user">user 1</div><div class="user">user 2</div></main>"#,
+/// );
+/// # }
+/// ```
+pub trait AsyncHtmlContent: Sized + Send {
+	/// Resolves the content, which can then be formatted.
+	fn resolve(self) -> impl Future<Output = impl HtmlContent + Send> + Send;
+
+	/// Resolves the content, and renders it into a [Bytes] buffer, awaiting the async content it
+	/// contains in document order.
+	///
+	/// # Returns
+	///
+	/// A [Result] containing the [Bytes] object if successful, or a [std::fmt::Error] if formatting fails.
+	fn into_bytes_async(self) -> impl Future<Output = Result<Bytes, fmt::Error>> + Send {
+		async move { self.resolve().await.render_async()?.into_bytes().await }
+	}
+
+	/// Resolves the content, and renders it into a [String], awaiting the async content it
+	/// contains in document order.
+	///
+	/// # Returns
+	///
+	/// A [Result] containing the [String] if successful, or a [std::fmt::Error] if formatting or
+	/// conversion to [String] fails.
+	fn into_string_async(self) -> impl Future<Output = Result<String, fmt::Error>> + Send {
+		async move {
+			let bytes = self.into_bytes_async().await?;
+			String::from_utf8(bytes.to_vec()).map_err(|_| fmt::Error)
+		}
+	}
+}
+
+impl<F> AsyncHtmlContent for F
+where
+	F: Future + Send,
+	F::Output: HtmlContent + Send,
+{
+	fn resolve(self) -> impl Future<Output = impl HtmlContent + Send> + Send {
+		self
+	}
+}
+
+/// A part of the output of an [AsyncRender].
+enum Segment {
+	Rendered(Bytes),
+	Pending(PendingContent),
+	Resolved(ResolvedContent),
+}
+
+/// Output that contains deferred content, split at the positions of the deferred content.
+#[derive(Default)]
+pub(crate) struct Segments(VecDeque<Segment>);
+
+impl Segments {
+	/// Renders content with a formatter that defers async content, and adds the content of
+	/// [Suspense](crate::Suspense) components to `suspense` when it's given.
+	pub(crate) fn render(
+		content: impl FnOnce(&mut HtmlFormatter) -> fmt::Result,
+		suspense: Option<&mut SuspenseQueue>,
+	) -> Result<Self, fmt::Error> {
+		let mut buffer = BytesMut::new();
+		let mut deferred = Vec::new();
+		content(&mut HtmlFormatter::with_deferred(
+			&mut buffer,
+			&mut deferred,
+			suspense,
+		))?;
+
+		let bytes = buffer.freeze();
+		let mut segments = VecDeque::with_capacity(deferred.len() * 2 + 1);
+		let mut start = 0;
+		for (position, content) in deferred {
+			if position > start {
+				segments.push_back(Segment::Rendered(bytes.slice(start..position)));
+			}
+
+			segments.push_back(Segment::Pending(content));
+			start = position;
+		}
+
+		if bytes.len() > start {
+			segments.push_back(Segment::Rendered(bytes.slice(start..)));
+		}
+
+		Ok(Self(segments))
+	}
+
+	/// Whether all of the output has been returned.
+	pub(crate) fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// Whether there is no deferred content left.
+	fn is_rendered(&self) -> bool {
+		self
+			.0
+			.iter()
+			.all(|segment| matches!(segment, Segment::Rendered(_)))
+	}
+
+	/// Polls the deferred content, and returns the next part of the output in document order. The
+	/// deferred content is rendered with `suspense` as its [SuspenseQueue], if it's given.
+	pub(crate) fn poll_next(
+		&mut self,
+		cx: &mut Context<'_>,
+		concurrent: bool,
+		mut suspense: Option<&mut SuspenseQueue>,
+	) -> Poll<Option<Result<Bytes, fmt::Error>>> {
+		loop {
+			let Some(segment) = self.0.pop_front() else {
+				return Poll::Ready(None);
+			};
+
+			match segment {
+				Segment::Rendered(bytes) => return Poll::Ready(Some(Ok(bytes))),
+
+				Segment::Pending(mut content) => match content.as_mut().poll(cx) {
+					Poll::Ready(content) => self.0.push_front(Segment::Resolved(content)),
+					Poll::Pending => {
+						self.0.push_front(Segment::Pending(content));
+						if concurrent {
+							self.poll_resolving(cx);
+						}
+
+						return Poll::Pending;
+					}
+				},
+
+				Segment::Resolved(content) => match Self::render(content, suspense.as_deref_mut()) {
+					Ok(segments) => {
+						for segment in segments.0.into_iter().rev() {
+							self.0.push_front(segment);
+						}
+					}
+					Err(error) => return Poll::Ready(Some(Err(error))),
+				},
+			}
+		}
+	}
+
+	/// Polls all deferred content after the next part of the output, so it's resolved concurrently.
+	fn poll_resolving(&mut self, cx: &mut Context<'_>) {
+		for segment in self.0.iter_mut().skip(1) {
+			if let Segment::Pending(content) = segment {
+				if let Poll::Ready(content) = content.as_mut().poll(cx) {
+					*segment = Segment::Resolved(content);
+				}
+			}
+		}
+	}
+}
+
+/// Renders content that contains async content, created by [HtmlContent::render_async] or
+/// [HtmlContent::render_streaming].
+///
+/// The content is rendered right away, and the async content it contains is deferred. The
+/// deferred content is resolved in document order, and rendered in place once it's resolved,
+/// deferring the async content that it contains in turn. When streaming, the content of
+/// [Suspense](crate::Suspense) components is written as chunks after the rest of the output,
+/// in the order it resolves.
+///
+/// By default, the deferred content is resolved one after the other. With
+/// [concurrent](Self::concurrent), all of it is resolved concurrently, while the output is still
+/// written in document order.
+///
+/// Deferred content is rendered after the content around it has been written, so errors while
+/// rendering it can't be caught by an [ErrorBoundary](crate::ErrorBoundary) that contains it
+/// as usual. The boundary instead defers the rest of its children along with the content, so
+/// they are still replaced by its fallback when rendering the content fails, as described there.
+/// Any other error is returned as the next part of the output, after the output before it has
+/// been returned, so content that may fail is best wrapped in an ErrorBoundary.
+pub struct AsyncRender {
+	segments: Segments,
+	suspense: Option<SuspenseQueue>,
+	concurrent: bool,
+}
+
+impl AsyncRender {
+	pub(crate) fn new(content: impl HtmlContent) -> Result<Self, fmt::Error> {
+		Ok(Self {
+			segments: Segments::render(|formatter| content.fmt(formatter), None)?,
+			suspense: None,
+			concurrent: false,
+		})
+	}
+
+	pub(crate) fn streaming(content: impl HtmlContent) -> Result<Self, fmt::Error> {
+		let mut suspense = SuspenseQueue::new();
+		Ok(Self {
+			segments: Segments::render(|formatter| content.fmt(formatter), Some(&mut suspense))?,
+			suspense: Some(suspense),
+			concurrent: false,
+		})
+	}
+
+	pub(crate) fn from_bytes(bytes: Bytes) -> Self {
+		Self {
+			segments: Segments(VecDeque::from([Segment::Rendered(bytes)])),
+			suspense: None,
+			concurrent: false,
+		}
+	}
+
+	/// Sets whether the deferred content is resolved concurrently.
+	pub fn concurrent(mut self, concurrent: bool) -> Self {
+		self.concurrent = concurrent;
+		self
+	}
+
+	/// Returns the output when there is no deferred or suspended content, so it doesn't have to be
+	/// awaited.
+	pub fn into_rendered(self) -> Result<Bytes, Self> {
+		if !self.segments.is_rendered()
+			|| self
+				.suspense
+				.as_ref()
+				.is_some_and(|suspense| !suspense.is_empty())
+		{
+			return Err(self);
+		}
+
+		let mut segments = self.segments.0.into_iter().map(|segment| match segment {
+			Segment::Rendered(bytes) => bytes,
+			_ => unreachable!("all segments are rendered"),
+		});
+
+		match (segments.next(), segments.len()) {
+			(None, _) => Ok(Bytes::new()),
+			(Some(bytes), 0) => Ok(bytes),
+			(Some(bytes), _) => {
+				let mut buffer = BytesMut::from(bytes.as_ref());
+				segments.for_each(|bytes| buffer.extend_from_slice(&bytes));
+				Ok(buffer.freeze())
+			}
+		}
+	}
+
+	/// Polls the deferred content, and returns the next part of the output in document order,
+	/// followed by the chunks of suspended content when streaming.
+	///
+	/// # Returns
+	///
+	/// - `Poll::Ready(Some(chunk))` with the next part of the output, where rendering it may have failed.
+	/// - `Poll::Ready(None)` when all of the output has been returned.
+	/// - `Poll::Pending` when the next part of the output is still being resolved. The waker of the
+	///   context is woken when it should be polled again.
+	pub fn poll_chunk(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes, fmt::Error>>> {
+		match self
+			.segments
+			.poll_next(cx, self.concurrent, self.suspense.as_mut())
+		{
+			Poll::Ready(None) => {}
+			Poll::Pending => {
+				// suspended content is resolved while the output is still being written
+				if let Some(suspense) = &mut self.suspense {
+					suspense.poll_pending(cx);
+				}
+
+				return Poll::Pending;
+			}
+			poll => return poll,
+		}
+
+		match &mut self.suspense {
+			Some(suspense) => suspense.poll_chunk(cx),
+			None => Poll::Ready(None),
+		}
+	}
+
+	/// Resolves the deferred content, and returns the complete output.
+	pub async fn into_bytes(mut self) -> Result<Bytes, fmt::Error> {
+		let mut buffer = BytesMut::new();
+		while let Some(chunk) = poll_fn(|cx| self.poll_chunk(cx)).await {
+			buffer.extend_from_slice(&chunk?);
+		}
+
+		Ok(buffer.freeze())
+	}
+}
+
+/// Resolves all of the content concurrently.
+pub(crate) async fn resolve_all(content: Vec<PendingContent>) -> Vec<ResolvedContent> {
+	let mut segments = content
+		.into_iter()
+		.map(Segment::Pending)
+		.collect::<Vec<_>>();
+	poll_fn(|cx| {
+		let mut resolved = true;
+		for segment in &mut segments {
+			if let Segment::Pending(content) = segment {
+				match content.as_mut().poll(cx) {
+					Poll::Ready(content) => *segment = Segment::Resolved(content),
+					Poll::Pending => resolved = false,
+				}
+			}
+		}
+
+		if resolved {
+			Poll::Ready(())
+		} else {
+			Poll::Pending
+		}
+	})
+	.await;
+
+	segments
+		.into_iter()
+		.map(|segment| match segment {
+			Segment::Resolved(content) => content,
+			_ => unreachable!("all content is resolved"),
+		})
+		.collect()
+}
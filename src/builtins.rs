@@ -1,19 +1,150 @@
-use crate::{HtmlComponent, HtmlContent, HtmlFormatter};
-use std::fmt;
+use crate::{
+	async_content::resolve_all,
+	suspense::{PendingContent, ResolvedContent},
+	HtmlComponent, HtmlContent, HtmlFormatter, HtmlRender,
+};
+use bytes::Bytes;
+use std::{
+	any::Any,
+	fmt,
+	iter::Peekable,
+	panic::{self, AssertUnwindSafe},
+};
 
-pub struct For<I, F>
+/// Renders its children for every item.
+///
+/// - `items`: The items to render. They are iterated once, without collecting them.
+/// - `children`: A closure that renders a single item.
+/// - `separator` (optional): Content rendered between consecutive items, such as `", "`.
+/// - `empty` (optional): Content rendered instead when there are no items.
+///
+/// Use [Iteration::enumerate] to also get the index of each item, and whether it's the first
+/// or last item.
+///
+/// ```
+/// # use rstml_component::{html, For, HtmlContent};
+/// let tags = ["rust", "html"];
+/// let html = html!(
+///     <For items={tags} separator=", " empty={html!(<i>"No tags"</i>)}>
+///         {|f, tag| f.write_content(tag)}
+///     </For>
+/// );
+///
+/// assert_eq!(html.into_string().unwrap(), "rust, html");
+/// ```
+pub struct For<I, F, S = (), E = ()> {
+	pub items: I,
+	pub children: F,
+	pub separator: S,
+	pub empty: E,
+}
+
+impl For<(), (), (), ()> {
+	/// Creates a builder for [For], where `items` and `children` must be set.
+	pub fn builder() -> ForBuilder<(), (), (), ()> {
+		ForBuilder(For {
+			items: (),
+			children: (),
+			separator: (),
+			empty: (),
+		})
+	}
+}
+
+/// A builder for [For], created by [For::builder].
+pub struct ForBuilder<I, F, S, E>(For<I, F, S, E>);
+
+impl<I, F, S, E> ForBuilder<I, F, S, E> {
+	pub fn items<T>(self, items: T) -> ForBuilder<T, F, S, E> {
+		let For {
+			children,
+			separator,
+			empty,
+			..
+		} = self.0;
+
+		ForBuilder(For {
+			items,
+			children,
+			separator,
+			empty,
+		})
+	}
+
+	pub fn separator<T>(self, separator: T) -> ForBuilder<I, F, T, E> {
+		let For {
+			items,
+			children,
+			empty,
+			..
+		} = self.0;
+
+		ForBuilder(For {
+			items,
+			children,
+			separator,
+			empty,
+		})
+	}
+
+	pub fn empty<T>(self, empty: T) -> ForBuilder<I, F, S, T> {
+		let For {
+			items,
+			children,
+			separator,
+			..
+		} = self.0;
+
+		ForBuilder(For {
+			items,
+			children,
+			separator,
+			empty,
+		})
+	}
+}
+
+impl<I: IntoIterator, F, S, E> ForBuilder<I, F, S, E> {
+	/// Sets the closure that renders a single item. Bounded here, after `items` is set, so that
+	/// the types of the closure arguments can be inferred.
+	pub fn children<T>(self, children: T) -> ForBuilder<I, T, S, E>
+	where
+		T: FnMut(&mut HtmlFormatter, <I as IntoIterator>::Item) -> fmt::Result,
+	{
+		let For {
+			items,
+			separator,
+			empty,
+			..
+		} = self.0;
+
+		ForBuilder(For {
+			items,
+			children,
+			separator,
+			empty,
+		})
+	}
+}
+
+impl<I, F, S, E> ForBuilder<I, F, S, E>
 where
 	I: IntoIterator,
 	F: FnMut(&mut HtmlFormatter, <I as IntoIterator>::Item) -> fmt::Result,
+	S: HtmlRender,
+	E: HtmlContent,
 {
-	pub items: I,
-	pub children: F,
+	pub fn build(self) -> For<I, F, S, E> {
+		self.0
+	}
 }
 
-impl<I, F> HtmlComponent for For<I, F>
+impl<I, F, S, E> HtmlComponent for For<I, F, S, E>
 where
 	I: IntoIterator,
 	F: FnMut(&mut HtmlFormatter, <I as IntoIterator>::Item) -> fmt::Result,
+	S: HtmlRender,
+	E: HtmlContent,
 {
 	type Content = Self;
 
@@ -22,21 +153,746 @@ where
 	}
 }
 
-impl<I, F> HtmlContent for For<I, F>
+impl<I, F, S, E> HtmlContent for For<I, F, S, E>
 where
 	I: IntoIterator,
 	F: FnMut(&mut HtmlFormatter, <I as IntoIterator>::Item) -> fmt::Result,
+	S: HtmlRender,
+	E: HtmlContent,
 {
 	fn fmt(self, formatter: &mut HtmlFormatter) -> std::fmt::Result {
 		let For {
 			items,
 			children: mut template,
+			separator,
+			empty,
 		} = self;
 
+		let mut items = items.into_iter();
+		let Some(first) = items.next() else {
+			return formatter.write_content(empty);
+		};
+
+		template(formatter, first)?;
 		for item in items {
+			separator.render(formatter)?;
 			template(formatter, item)?;
 		}
 
 		Ok(())
 	}
 }
+
+/// The position of an item in the items of a loop, as given by [Iteration::enumerate].
+///
+/// ```
+/// # use rstml_component::{html, For, HtmlContent, Iteration};
+/// let rows = ["a", "b", "c"];
+/// let html = html!(
+///     <For items={Iteration::enumerate(rows)}>
+///         {|f, (iteration, row): (Iteration, &str)| rstml_component::write_html!(f,
+///             <tr class:odd={iteration.is_odd()} class:last={iteration.is_last()}>
+///                 <td>{iteration.index() + 1}</td><td>{row}</td>
+///             </tr>
+///         )}
+///     </For>
+/// );
+///
+/// assert_eq!(
+///     html.into_string().unwrap(),
+///     r#"<tr><td>1</td><td>a</td></tr><tr class="odd"><td>2</td><td>b</td></tr><tr class="last"><td>3</td><td>c</td></tr>"#,
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Iteration {
+	index: usize,
+	is_last: bool,
+}
+
+impl Iteration {
+	/// Pairs each item with its [Iteration]. Whether an item is the last one is determined by
+	/// looking ahead a single item, so the items are not collected.
+	pub fn enumerate<I: IntoIterator>(items: I) -> Iterations<I::IntoIter> {
+		Iterations {
+			items: items.into_iter().peekable(),
+			index: 0,
+		}
+	}
+
+	/// The index of the item, starting at `0`.
+	pub fn index(&self) -> usize {
+		self.index
+	}
+
+	/// Whether this is the first item.
+	pub fn is_first(&self) -> bool {
+		self.index == 0
+	}
+
+	/// Whether this is the last item.
+	pub fn is_last(&self) -> bool {
+		self.is_last
+	}
+
+	/// Whether the index of the item is even, which is the case for the first item.
+	pub fn is_even(&self) -> bool {
+		self.index.is_multiple_of(2)
+	}
+
+	/// Whether the index of the item is odd.
+	pub fn is_odd(&self) -> bool {
+		!self.is_even()
+	}
+}
+
+/// An iterator that pairs items with their [Iteration], created by [Iteration::enumerate].
+pub struct Iterations<I: Iterator> {
+	items: Peekable<I>,
+	index: usize,
+}
+
+impl<I: Iterator> Iterator for Iterations<I> {
+	type Item = (Iteration, I::Item);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.items.next()?;
+		let iteration = Iteration {
+			index: self.index,
+			is_last: self.items.peek().is_none(),
+		};
+
+		self.index += 1;
+		Some((iteration, item))
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.items.size_hint()
+	}
+}
+
+/// The condition of [Show] and [Match], which is either a `bool` or a closure returning one. A
+/// closure is only called when the result is needed, for instance when no earlier case of a
+/// [Switch] has matched.
+pub trait Condition {
+	fn test(self) -> bool;
+}
+
+impl Condition for bool {
+	fn test(self) -> bool {
+		self
+	}
+}
+
+impl<F> Condition for F
+where
+	F: FnOnce() -> bool,
+{
+	fn test(self) -> bool {
+		self()
+	}
+}
+
+/// Renders its children when a condition holds.
+///
+/// - `when`: The [Condition] to test.
+/// - `children`: The content to render when the condition holds.
+/// - `fallback` (optional): Content rendered instead when the condition doesn't hold.
+///
+/// ```
+/// # use rstml_component::{html, HtmlContent, Show};
+/// let user: Option<&str> = None;
+/// let html = html!(
+///     <Show when={user.is_some()} fallback={html!(<a href="/login">"Log in"</a>)}>
+///         <span>"Welcome back"</span>
+///     </Show>
+/// );
+///
+/// assert_eq!(html.into_string().unwrap(), r#"<a href="/login">Log in</a>"#);
+/// ```
+pub struct Show<W, C, F = ()> {
+	pub when: W,
+	pub children: C,
+	pub fallback: F,
+}
+
+impl Show<(), (), ()> {
+	/// Creates a builder for [Show], where `when` and `children` must be set.
+	pub fn builder() -> ShowBuilder<(), (), ()> {
+		ShowBuilder(Show {
+			when: (),
+			children: (),
+			fallback: (),
+		})
+	}
+}
+
+/// A builder for [Show], created by [Show::builder].
+pub struct ShowBuilder<W, C, F>(Show<W, C, F>);
+
+impl<W, C, F> ShowBuilder<W, C, F> {
+	pub fn when<T>(self, when: T) -> ShowBuilder<T, C, F> {
+		let Show {
+			children, fallback, ..
+		} = self.0;
+
+		ShowBuilder(Show {
+			when,
+			children,
+			fallback,
+		})
+	}
+
+	pub fn children<T>(self, children: T) -> ShowBuilder<W, T, F> {
+		let Show { when, fallback, .. } = self.0;
+
+		ShowBuilder(Show {
+			when,
+			children,
+			fallback,
+		})
+	}
+
+	pub fn fallback<T>(self, fallback: T) -> ShowBuilder<W, C, T> {
+		let Show { when, children, .. } = self.0;
+
+		ShowBuilder(Show {
+			when,
+			children,
+			fallback,
+		})
+	}
+}
+
+impl<W, C, F> ShowBuilder<W, C, F>
+where
+	W: Condition,
+	C: HtmlContent,
+	F: HtmlContent,
+{
+	pub fn build(self) -> Show<W, C, F> {
+		self.0
+	}
+}
+
+impl<W, C, F> HtmlComponent for Show<W, C, F>
+where
+	W: Condition,
+	C: HtmlContent,
+	F: HtmlContent,
+{
+	type Content = Self;
+
+	fn into_content(self) -> Self::Content {
+		self
+	}
+}
+
+impl<W, C, F> HtmlContent for Show<W, C, F>
+where
+	W: Condition,
+	C: HtmlContent,
+	F: HtmlContent,
+{
+	fn fmt(self, formatter: &mut HtmlFormatter) -> fmt::Result {
+		if self.when.test() {
+			formatter.write_content(self.children)
+		} else {
+			formatter.write_content(self.fallback)
+		}
+	}
+}
+
+/// Renders the first of its [Match] cases whose condition holds.
+///
+/// - `children`: The content containing the [Match] cases. Any other content is rendered as is.
+/// - `fallback` (optional): Content rendered when none of the cases matched.
+///
+/// A case selects on a value by comparing it in its condition, or on a predicate by passing a
+/// closure, which is only called when no earlier case has matched.
+///
+/// ```
+/// # use rstml_component::{html, HtmlContent, Match, Switch};
+/// #[derive(PartialEq)]
+/// enum Status {
+///     Draft,
+///     Published(u32),
+///     Archived,
+/// }
+///
+/// let status = Status::Published(3);
+/// let html = html!(
+///     <Switch fallback={html!(<span>"Archived"</span>)}>
+///         <Match when={status == Status::Draft}>"Draft"</Match>
+///         <Match when={|| matches!(status, Status::Published(views) if views > 100)}>"Popular"</Match>
+///         <Match when={|| matches!(status, Status::Published(_))}>"Published"</Match>
+///     </Switch>
+/// );
+///
+/// assert_eq!(html.into_string().unwrap(), "Published");
+/// ```
+pub struct Switch<C, F = ()> {
+	pub children: C,
+	pub fallback: F,
+}
+
+impl Switch<(), ()> {
+	/// Creates a builder for [Switch], where `children` must be set.
+	pub fn builder() -> SwitchBuilder<(), ()> {
+		SwitchBuilder(Switch {
+			children: (),
+			fallback: (),
+		})
+	}
+}
+
+/// A builder for [Switch], created by [Switch::builder].
+pub struct SwitchBuilder<C, F>(Switch<C, F>);
+
+impl<C, F> SwitchBuilder<C, F> {
+	pub fn children<T>(self, children: T) -> SwitchBuilder<T, F> {
+		let Switch { fallback, .. } = self.0;
+
+		SwitchBuilder(Switch { children, fallback })
+	}
+
+	pub fn fallback<T>(self, fallback: T) -> SwitchBuilder<C, T> {
+		let Switch { children, .. } = self.0;
+
+		SwitchBuilder(Switch { children, fallback })
+	}
+}
+
+impl<C, F> SwitchBuilder<C, F>
+where
+	C: HtmlContent,
+	F: HtmlContent,
+{
+	pub fn build(self) -> Switch<C, F> {
+		self.0
+	}
+}
+
+impl<C, F> HtmlComponent for Switch<C, F>
+where
+	C: HtmlContent,
+	F: HtmlContent,
+{
+	type Content = Self;
+
+	fn into_content(self) -> Self::Content {
+		self
+	}
+}
+
+impl<C, F> HtmlContent for Switch<C, F>
+where
+	C: HtmlContent,
+	F: HtmlContent,
+{
+	fn fmt(self, formatter: &mut HtmlFormatter) -> fmt::Result {
+		let outer = formatter.replace_switch(Some(false));
+		let result = formatter.write_content(self.children);
+		let matched = formatter.replace_switch(outer) == Some(true);
+		result?;
+
+		if matched {
+			Ok(())
+		} else {
+			formatter.write_content(self.fallback)
+		}
+	}
+}
+
+/// A case of a [Switch], which renders its children when its condition holds and no earlier
+/// case of the switch has matched. Outside of a switch, it renders its children when its
+/// condition holds.
+#[derive(HtmlComponent)]
+pub struct Match<W, C>
+where
+	W: Condition,
+	C: HtmlContent,
+{
+	pub when: W,
+	pub children: C,
+}
+
+impl<W, C> HtmlContent for Match<W, C>
+where
+	W: Condition,
+	C: HtmlContent,
+{
+	fn fmt(self, formatter: &mut HtmlFormatter) -> fmt::Result {
+		let Match { when, children } = self;
+
+		// a case that matched before decides the switch
+		let switch = formatter.replace_switch(None);
+		if switch == Some(true) || !when.test() {
+			formatter.replace_switch(switch);
+			return Ok(());
+		}
+
+		// cases in the children belong to their own switch, if any
+		let result = formatter.write_content(children);
+		formatter.replace_switch(switch.map(|_| true));
+		result
+	}
+}
+
+/// Renders its children, or a fallback when rendering them fails.
+///
+/// - `children`: The content to render.
+/// - `fallback` (optional): A closure that renders the fallback, given the [CaughtError].
+///   Nothing is rendered when it's not set.
+/// - `on_error` (optional): A closure that is called with the [CaughtError] before the fallback
+///   is rendered, for instance to log it.
+/// - `catch_panics` (optional): Whether panics while rendering the children are caught as well.
+///   Panics are still reported by the panic hook, and can't be caught when panics abort.
+///
+/// When rendering the children fails, everything they have written is discarded, so the
+/// fallback replaces them completely.
+///
+/// Async content in the children is rendered after the boundary has returned, so when the
+/// children contain async content, the rest of their output is deferred along with it until all
+/// of it has resolved. If rendering it fails, the output of the children is replaced by the
+/// fallback, which is rendered ahead of time for a [CaughtError::Error]. `on_error` isn't called
+/// for these errors, as it can't be kept until the content has resolved.
+///
+/// ```
+/// # use rstml_component::{html, write_html, ErrorBoundary, HtmlContent, HtmlFormatter};
+/// fn broken(_: &mut HtmlFormatter) -> std::fmt::Result {
+///     Err(std::fmt::Error)
+/// }
+///
+/// let html = html!(
+///     <main>
+///         <ErrorBoundary fallback={|f, error| write_html!(f, <p class="error">{error.to_string()}</p>)}>
+///             <aside>{broken}</aside>
+///         </ErrorBoundary>
+///     </main>
+/// );
+///
+/// assert_eq!(
+///     html.into_string().unwrap(),
+///     r#"<main><p class="error">an error occurred while rendering</p></main>"#,
+/// );
+/// ```
+pub struct ErrorBoundary<C, F = (), H = ()> {
+	pub children: C,
+	pub fallback: F,
+	pub on_error: H,
+	pub catch_panics: bool,
+}
+
+impl ErrorBoundary<(), (), ()> {
+	/// Creates a builder for [ErrorBoundary], where `children` must be set.
+	pub fn builder() -> ErrorBoundaryBuilder<(), (), ()> {
+		ErrorBoundaryBuilder(ErrorBoundary {
+			children: (),
+			fallback: (),
+			on_error: (),
+			catch_panics: false,
+		})
+	}
+}
+
+/// A builder for [ErrorBoundary], created by [ErrorBoundary::builder].
+pub struct ErrorBoundaryBuilder<C, F, H>(ErrorBoundary<C, F, H>);
+
+impl<C, F, H> ErrorBoundaryBuilder<C, F, H> {
+	pub fn children<T>(self, children: T) -> ErrorBoundaryBuilder<T, F, H> {
+		let ErrorBoundary {
+			fallback,
+			on_error,
+			catch_panics,
+			..
+		} = self.0;
+
+		ErrorBoundaryBuilder(ErrorBoundary {
+			children,
+			fallback,
+			on_error,
+			catch_panics,
+		})
+	}
+
+	pub fn fallback<T>(self, fallback: T) -> ErrorBoundaryBuilder<C, T, H>
+	where
+		T: FnOnce(&mut HtmlFormatter, &CaughtError) -> fmt::Result,
+	{
+		let ErrorBoundary {
+			children,
+			on_error,
+			catch_panics,
+			..
+		} = self.0;
+
+		ErrorBoundaryBuilder(ErrorBoundary {
+			children,
+			fallback,
+			on_error,
+			catch_panics,
+		})
+	}
+
+	pub fn on_error<T>(self, on_error: T) -> ErrorBoundaryBuilder<C, F, T>
+	where
+		T: FnOnce(&CaughtError),
+	{
+		let ErrorBoundary {
+			children,
+			fallback,
+			catch_panics,
+			..
+		} = self.0;
+
+		ErrorBoundaryBuilder(ErrorBoundary {
+			children,
+			fallback,
+			on_error,
+			catch_panics,
+		})
+	}
+
+	pub fn catch_panics(mut self, catch_panics: bool) -> Self {
+		self.0.catch_panics = catch_panics;
+		self
+	}
+}
+
+impl<C, F, H> ErrorBoundaryBuilder<C, F, H>
+where
+	C: HtmlContent,
+	F: ErrorFallback,
+	H: ErrorHook,
+{
+	pub fn build(self) -> ErrorBoundary<C, F, H> {
+		self.0
+	}
+}
+
+impl<C, F, H> HtmlComponent for ErrorBoundary<C, F, H>
+where
+	C: HtmlContent,
+	F: ErrorFallback,
+	H: ErrorHook,
+{
+	type Content = Self;
+
+	fn into_content(self) -> Self::Content {
+		self
+	}
+}
+
+impl<C, F, H> HtmlContent for ErrorBoundary<C, F, H>
+where
+	C: HtmlContent,
+	F: ErrorFallback,
+	H: ErrorHook,
+{
+	fn fmt(self, formatter: &mut HtmlFormatter) -> fmt::Result {
+		let ErrorBoundary {
+			children,
+			fallback,
+			on_error,
+			catch_panics,
+		} = self;
+
+		let state = formatter.save();
+		let result = if catch_panics {
+			panic::catch_unwind(AssertUnwindSafe(|| {
+				formatter
+					.write_content(children)
+					.map_err(CaughtError::Error)
+			}))
+			.unwrap_or_else(|payload| Err(CaughtError::from_panic(payload)))
+		} else {
+			formatter
+				.write_content(children)
+				.map_err(CaughtError::Error)
+		};
+
+		let Err(error) = result else {
+			if let Some((output, deferred)) = formatter.take_deferred(&state) {
+				let error = CaughtError::Error(fmt::Error);
+				let fallback = formatter.render_separately(|formatter| fallback.render(formatter, &error));
+				formatter.defer(defer_children(output, deferred, fallback, catch_panics))?;
+			}
+
+			return Ok(());
+		};
+
+		formatter.restore(state);
+		on_error.report(&error);
+		fallback.render(formatter, &error)
+	}
+}
+
+/// Defers the output of the children of an [ErrorBoundary] along with the async content in it,
+/// rendering the fallback instead when rendering the content fails.
+fn defer_children(
+	output: Bytes,
+	deferred: Vec<(usize, PendingContent)>,
+	fallback: Result<Bytes, fmt::Error>,
+	catch_panics: bool,
+) -> PendingContent {
+	Box::pin(async move {
+		let (positions, content): (Vec<_>, Vec<_>) = deferred.into_iter().unzip();
+		let content = resolve_all(content).await;
+
+		Box::new(move |formatter: &mut HtmlFormatter| {
+			let state = formatter.save();
+			let write = |formatter: &mut HtmlFormatter| {
+				let mut start = 0;
+				for (position, content) in positions.into_iter().zip(content) {
+					formatter.write_bytes(&output[start..position]);
+					content(formatter)?;
+					start = position;
+				}
+
+				formatter.write_bytes(&output[start..]);
+				Ok(())
+			};
+
+			let result = if catch_panics {
+				panic::catch_unwind(AssertUnwindSafe(|| write(formatter))).unwrap_or(Err(fmt::Error))
+			} else {
+				write(formatter)
+			};
+
+			if result.is_err() {
+				formatter.restore(state);
+				formatter.write_bytes(&fallback?);
+				return Ok(());
+			}
+
+			// content that defers content in turn is deferred again, so it's still replaced by the
+			// fallback when that fails
+			match formatter.take_deferred(&state) {
+				Some((output, deferred)) => {
+					formatter.defer(defer_children(output, deferred, fallback, catch_panics))
+				}
+				None => Ok(()),
+			}
+		}) as ResolvedContent
+	})
+}
+
+/// An error caught by an [ErrorBoundary].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaughtError {
+	/// Rendering the children returned an error.
+	Error(fmt::Error),
+	/// Rendering the children panicked, with the given message.
+	Panic(String),
+}
+
+impl CaughtError {
+	fn from_panic(payload: Box<dyn Any + Send>) -> Self {
+		let message = match payload.downcast::<String>() {
+			Ok(message) => *message,
+			Err(payload) => match payload.downcast::<&'static str>() {
+				Ok(message) => (*message).to_owned(),
+				Err(_) => String::from("Box<dyn Any>"),
+			},
+		};
+
+		Self::Panic(message)
+	}
+}
+
+impl fmt::Display for CaughtError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Error(_) => f.write_str("an error occurred while rendering"),
+			Self::Panic(message) => write!(f, "rendering panicked: {message}"),
+		}
+	}
+}
+
+impl std::error::Error for CaughtError {}
+
+/// The fallback of an [ErrorBoundary], which is either `()` to render nothing, or a closure
+/// that renders the fallback given the [CaughtError].
+pub trait ErrorFallback {
+	fn render(self, formatter: &mut HtmlFormatter, error: &CaughtError) -> fmt::Result;
+}
+
+impl ErrorFallback for () {
+	fn render(self, _formatter: &mut HtmlFormatter, _error: &CaughtError) -> fmt::Result {
+		Ok(())
+	}
+}
+
+impl<F> ErrorFallback for F
+where
+	F: FnOnce(&mut HtmlFormatter, &CaughtError) -> fmt::Result,
+{
+	fn render(self, formatter: &mut HtmlFormatter, error: &CaughtError) -> fmt::Result {
+		self(formatter, error)
+	}
+}
+
+/// The error hook of an [ErrorBoundary], which is either `()` to ignore errors, or a closure
+/// that is called with the [CaughtError].
+pub trait ErrorHook {
+	fn report(self, error: &CaughtError);
+}
+
+impl ErrorHook for () {
+	fn report(self, _error: &CaughtError) {}
+}
+
+impl<F> ErrorHook for F
+where
+	F: FnOnce(&CaughtError),
+{
+	fn report(self, error: &CaughtError) {
+		self(error)
+	}
+}
+
+/// Provides a context value to its children, which they can read through
+/// [HtmlFormatter::context], or through `#[context]` arguments of
+/// [component](crate::component) functions.
+///
+/// - `value`: The context value to provide.
+/// - `children`: The content that the value is provided to.
+///
+/// ```
+/// # use rstml_component::{component, html, HtmlContent, Provider};
+/// # use std::sync::Arc;
+/// struct CurrentUser(&'static str);
+///
+/// #[component(UserMenu)]
+/// fn user_menu(#[context] user: Option<Arc<CurrentUser>>) -> impl HtmlContent {
+///     html!(<nav>{user.map_or("Guest", |user| user.0)}</nav>)
+/// }
+///
+/// let html = html!(
+///     <Provider value={CurrentUser("alice")}>
+///         <header><UserMenu /></header>
+///     </Provider>
+///     <UserMenu />
+/// );
+///
+/// assert_eq!(html.into_string().unwrap(), "<header><nav>alice</nav></header><nav>Guest</nav>");
+/// ```
+#[derive(HtmlComponent)]
+pub struct Provider<T, C>
+where
+	T: Any + Send + Sync,
+	C: HtmlContent,
+{
+	pub value: T,
+	pub children: C,
+}
+
+impl<T, C> HtmlContent for Provider<T, C>
+where
+	T: Any + Send + Sync,
+	C: HtmlContent,
+{
+	fn fmt(self, formatter: &mut HtmlFormatter) -> fmt::Result {
+		formatter.provide(self.value, self.children)
+	}
+}
@@ -0,0 +1,76 @@
+use crate::HtmlFormatter;
+use std::{
+	any::{Any, TypeId},
+	collections::HashMap,
+	fmt,
+	sync::Arc,
+};
+
+/// A typed store of context values, which are passed down to the content written by a
+/// [HtmlFormatter] without threading them through props.
+///
+/// Values are stored by their type, so there is a single value of each type. Cloning the store
+/// is cheap, as the values are shared.
+///
+/// ```
+/// # use rstml_component::ContextStore;
+/// struct Locale(&'static str);
+///
+/// let mut context = ContextStore::new();
+/// context.insert(Locale("nl"));
+///
+/// assert_eq!(context.get::<Locale>().unwrap().0, "nl");
+/// assert!(context.get::<String>().is_none());
+/// ```
+#[derive(Clone, Default)]
+pub struct ContextStore {
+	values: Arc<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+}
+
+impl ContextStore {
+	/// Creates an empty store.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Inserts a value, replacing the value of the same type if there is one.
+	pub fn insert<T: Any + Send + Sync>(&mut self, value: T) {
+		Arc::make_mut(&mut self.values).insert(TypeId::of::<T>(), Arc::new(value));
+	}
+
+	/// Returns the value of the given type, if there is one.
+	pub fn get<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+		let value = self.values.get(&TypeId::of::<T>())?.clone();
+		value.downcast().ok()
+	}
+}
+
+impl fmt::Debug for ContextStore {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ContextStore")
+			.field("len", &self.values.len())
+			.finish()
+	}
+}
+
+/// A trait for the arguments of [component](crate::component) functions that are marked with
+/// `#[context]`, which are read from the context of the formatter instead of being passed as
+/// props.
+///
+/// - `Arc<T>` reads the value of type `T`, and fails to render when there is none.
+/// - `Option<Arc<T>>` reads the value of type `T`, if there is one.
+pub trait FromContext: Sized {
+	fn from_context(formatter: &HtmlFormatter) -> Result<Self, fmt::Error>;
+}
+
+impl<T: Any + Send + Sync> FromContext for Arc<T> {
+	fn from_context(formatter: &HtmlFormatter) -> Result<Self, fmt::Error> {
+		formatter.context().ok_or(fmt::Error)
+	}
+}
+
+impl<T: Any + Send + Sync> FromContext for Option<Arc<T>> {
+	fn from_context(formatter: &HtmlFormatter) -> Result<Self, fmt::Error> {
+		Ok(formatter.context())
+	}
+}
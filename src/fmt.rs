@@ -1,8 +1,17 @@
+use crate::{
+	suspense::{PendingContent, ResolvedContent, SuspenseQueue},
+	AsyncHtmlContent, AsyncRender, ContextStore,
+};
 use bytes::{BufMut, Bytes, BytesMut};
-use std::fmt;
+use std::{any::Any, fmt, sync::Arc};
 
+mod attribute_lists;
+mod elements;
 mod escape;
 
+pub use attribute_lists::{Classes, Styles};
+pub use elements::ElementConfig;
+
 /// A formatter for serializing HTML attribute values.
 pub struct HtmlAttributeFormatter<'a> {
 	any_written: bool,
@@ -73,6 +82,26 @@ impl<'a> HtmlAttributeFormatter<'a> {
 		self.buffer.reserve(additional + 3);
 	}
 
+	/// Formats the value, returning the escaped value without the surrounding `="` and `"`.
+	pub(crate) fn escaped_value(value: impl HtmlAttributeValue) -> Result<BytesMut, fmt::Error> {
+		Ok(Self::optional_value(value)?.unwrap_or_default())
+	}
+
+	/// Formats the value like [escaped_value](Self::escaped_value), returning `None` when no value
+	/// is written, such as for boolean attributes.
+	fn optional_value(value: impl HtmlAttributeValue) -> Result<Option<BytesMut>, fmt::Error> {
+		let mut buffer = BytesMut::new();
+		let mut attribute_formatter = HtmlAttributeFormatter::new(&mut buffer);
+
+		value.fmt(&mut attribute_formatter)?;
+		if !attribute_formatter.any_written {
+			return Ok(None);
+		}
+
+		let _ = buffer.split_to(2);
+		Ok(Some(buffer))
+	}
+
 	fn write_value(buffer: &mut BytesMut, value: impl HtmlAttributeValue) -> fmt::Result {
 		let mut attribute_formatter = HtmlAttributeFormatter::new(buffer);
 
@@ -96,6 +125,15 @@ impl<'a> HtmlAttributeFormatter<'a> {
 /// later.
 pub struct HtmlFormatter<'a> {
 	buffer: &'a mut BytesMut,
+	elements: &'a ElementConfig,
+	in_raw_text: bool,
+	/// The attributes of the tag started by [write_open_tag_start](Self::write_open_tag_start),
+	/// which are written when anything else is written.
+	open_tag: Option<Vec<OpenTagAttribute>>,
+	switch: Option<bool>,
+	suspense: Option<&'a mut SuspenseQueue>,
+	deferred: Option<&'a mut Vec<(usize, PendingContent)>>,
+	context: ContextStore,
 }
 
 impl<'a> AsMut<HtmlFormatter<'a>> for HtmlFormatter<'a> {
@@ -115,7 +153,97 @@ impl<'a> HtmlFormatter<'a> {
 	///
 	/// A new `HtmlFormatter` instance associated with the provided buffer.
 	pub fn new(buffer: &'a mut BytesMut) -> Self {
-		Self { buffer }
+		Self::with_elements(buffer, &elements::HTML_ELEMENTS)
+	}
+
+	/// Creates a new `HtmlFormatter` instance with the provided buffer, which uses the given
+	/// configuration to determine which elements are void and raw text elements.
+	///
+	/// # Arguments
+	///
+	/// - `buffer`: A mutable reference to the [BytesMut] buffer where the formatted content will be written.
+	/// - `elements`: The [ElementConfig] used by [write_element](Self::write_element).
+	///
+	/// # Returns
+	///
+	/// A new `HtmlFormatter` instance associated with the provided buffer.
+	pub fn with_elements(buffer: &'a mut BytesMut, elements: &'a ElementConfig) -> Self {
+		Self {
+			buffer,
+			elements,
+			in_raw_text: false,
+			open_tag: None,
+			switch: None,
+			suspense: None,
+			deferred: None,
+			context: ContextStore::new(),
+		}
+	}
+
+	/// Creates a new `HtmlFormatter` instance with the provided buffer, which adds the content of
+	/// [Suspense](crate::Suspense) components to the given queue instead of only rendering their
+	/// fallback.
+	///
+	/// # Arguments
+	///
+	/// - `buffer`: A mutable reference to the [BytesMut] buffer where the formatted content will be written.
+	/// - `suspense`: The [SuspenseQueue] that the pending content is added to.
+	///
+	/// # Returns
+	///
+	/// A new `HtmlFormatter` instance associated with the provided buffer.
+	pub fn with_suspense(buffer: &'a mut BytesMut, suspense: &'a mut SuspenseQueue) -> Self {
+		Self {
+			suspense: Some(suspense),
+			..Self::new(buffer)
+		}
+	}
+
+	/// Creates a new `HtmlFormatter` instance with the provided buffer, which defers async
+	/// content by adding it to `deferred`, along with the position in the buffer to render it at.
+	/// The content of [Suspense](crate::Suspense) components is added to `suspense` when it's
+	/// given, and deferred like async content otherwise.
+	pub(crate) fn with_deferred(
+		buffer: &'a mut BytesMut,
+		deferred: &'a mut Vec<(usize, PendingContent)>,
+		suspense: Option<&'a mut SuspenseQueue>,
+	) -> Self {
+		Self {
+			deferred: Some(deferred),
+			suspense,
+			..Self::new(buffer)
+		}
+	}
+
+	/// Returns the configuration used to determine which elements are void and raw text elements.
+	pub fn elements(&self) -> &ElementConfig {
+		self.elements
+	}
+
+	/// Writes content with a different configuration of void and raw text elements, restoring
+	/// the current configuration afterwards.
+	///
+	/// Templates that change the void or raw text elements with `#![html(...)]` write their
+	/// content this way, so [write_element](Self::write_element) treats elements the same way as
+	/// the template does.
+	///
+	/// # Arguments
+	///
+	/// - `elements`: The [ElementConfig] used while writing the content.
+	/// - `content`: The content to write.
+	///
+	/// # Returns
+	///
+	/// A [std::fmt::Result] indicating the success or failure of the writing operation.
+	pub fn write_with_elements(
+		&mut self,
+		elements: &'a ElementConfig,
+		content: impl HtmlContent,
+	) -> fmt::Result {
+		let previous = std::mem::replace(&mut self.elements, elements);
+		let result = self.write_content(content);
+		self.elements = previous;
+		result
 	}
 
 	/// Writes raw bytes to the formatter's buffer without escaping.
@@ -129,6 +257,7 @@ impl<'a> HtmlFormatter<'a> {
 	///
 	/// - `raw`: A reference to the raw byte slice that will be written to the buffer.
 	pub fn write_bytes(&mut self, raw: &[u8]) {
+		self.write_open_tag_attributes();
 		self.buffer.extend_from_slice(raw);
 	}
 
@@ -142,7 +271,11 @@ impl<'a> HtmlFormatter<'a> {
 	///
 	/// - `value`: A reference to the raw byte slice containing the content to be escaped and written.
 	pub fn write(&mut self, value: &[u8]) {
-		self.write_bytes(&escape::text(value))
+		if self.in_raw_text {
+			self.write_bytes(&escape::raw_text(value))
+		} else {
+			self.write_bytes(&escape::text(value))
+		}
 	}
 
 	// Writes a DOCTYPE declaration to the formatter's buffer.
@@ -174,6 +307,9 @@ impl<'a> HtmlFormatter<'a> {
 	/// is used as the tag name, and the tag is not closed. is commonly followed by either [write_attribute_name],
 	/// [write_self_close_tag], or [write_open_tag_end].
 	///
+	/// The attributes of the tag are kept by the formatter until anything else is written, such as
+	/// the end of the tag, so that [write_attribute](Self::write_attribute) can merge duplicates.
+	///
 	/// # Arguments
 	///
 	/// - `tag`: A reference to the raw byte slice containing the tag name for the opening tag.
@@ -185,6 +321,7 @@ impl<'a> HtmlFormatter<'a> {
 		self.buffer.reserve(tag.len() + 1);
 		self.write_bytes(b"<");
 		self.write_bytes(tag);
+		self.open_tag = Some(Vec::new());
 	}
 
 	/// Writes an HTML attribute name to the formatter's buffer.
@@ -196,6 +333,14 @@ impl<'a> HtmlFormatter<'a> {
 	///
 	/// - `name`: A reference to the raw byte slice containing the attribute name.
 	pub fn write_attribute_name(&mut self, name: &[u8]) {
+		if let Some(attributes) = &mut self.open_tag {
+			attributes.push(OpenTagAttribute {
+				name: name.to_vec(),
+				value: None,
+			});
+			return;
+		}
+
 		self.buffer.reserve(name.len() + 1);
 		self.write_bytes(b" ");
 		self.write_bytes(name);
@@ -215,9 +360,68 @@ impl<'a> HtmlFormatter<'a> {
 	///
 	/// A [std::fmt::Result] indicating the success or failure of the writing operation.
 	pub fn write_attribute_value(&mut self, value: impl HtmlAttributeValue) -> fmt::Result {
+		if let Some(attribute) = self
+			.open_tag
+			.as_mut()
+			.and_then(|attributes| attributes.last_mut())
+		{
+			attribute.value = HtmlAttributeFormatter::optional_value(value)?;
+			return Ok(());
+		}
+
+		self.write_open_tag_attributes();
 		HtmlAttributeFormatter::write_value(self.buffer, value)
 	}
 
+	/// Writes an HTML attribute with the given name and value, unless the value is not present
+	/// (see [HtmlAttributeValue::is_present]), in which case nothing is written.
+	///
+	/// Browsers ignore all but the first of duplicate attributes, so if the tag started with
+	/// [write_open_tag_start](Self::write_open_tag_start) already has an attribute with the same
+	/// name, it is updated instead: `class` and `style` values are merged with the existing value,
+	/// and the value of any other attribute replaces the existing one.
+	///
+	/// # Arguments
+	///
+	/// - `name`: A reference to the raw byte slice containing the attribute name.
+	/// - `value`: An instance implementing the [HtmlAttributeValue] trait, representing the attribute value.
+	///
+	/// # Returns
+	///
+	/// A [std::fmt::Result] indicating the success or failure of the writing operation.
+	pub fn write_attribute(&mut self, name: &[u8], value: impl HtmlAttributeValue) -> fmt::Result {
+		if !value.is_present() {
+			return Ok(());
+		}
+
+		let Some(attributes) = &mut self.open_tag else {
+			self.write_attribute_name(name);
+			return self.write_attribute_value(value);
+		};
+
+		let Some(existing) = attributes
+			.iter_mut()
+			.find(|attribute| attribute.name.eq_ignore_ascii_case(name))
+		else {
+			attributes.push(OpenTagAttribute {
+				name: name.to_vec(),
+				value: HtmlAttributeFormatter::optional_value(value)?,
+			});
+			return Ok(());
+		};
+
+		let current = RawText::new(existing.value.clone().unwrap_or_default());
+		existing.value = if name.eq_ignore_ascii_case(b"class") {
+			HtmlAttributeFormatter::optional_value(Classes::new().with(current)?.with(value)?)?
+		} else if name.eq_ignore_ascii_case(b"style") {
+			HtmlAttributeFormatter::optional_value(Styles::new().with(current)?.with(value)?)?
+		} else {
+			HtmlAttributeFormatter::optional_value(value)?
+		};
+
+		Ok(())
+	}
+
 	pub fn write_attributes(&mut self, values: impl HtmlAttributes) -> fmt::Result {
 		let mut attribute_formatter = HtmlAttributesFormatter { inner: self };
 		values.fmt(&mut attribute_formatter)
@@ -239,6 +443,26 @@ impl<'a> HtmlFormatter<'a> {
 		self.write_bytes(b">");
 	}
 
+	/// Writes the attributes of the open tag, if any, after which attributes are written directly.
+	fn write_open_tag_attributes(&mut self) {
+		let Some(attributes) = self.open_tag.take() else {
+			return;
+		};
+
+		for OpenTagAttribute { name, value } in attributes {
+			self
+				.buffer
+				.reserve(name.len() + value.as_ref().map_or(0, |value| value.len() + 3) + 1);
+			self.buffer.put_slice(b" ");
+			self.buffer.put_slice(&name);
+			if let Some(value) = value {
+				self.buffer.put_slice(b"=\"");
+				self.buffer.put_slice(&value);
+				self.buffer.put_slice(b"\"");
+			}
+		}
+	}
+
 	/// Writes an HTML end tag to the formatter's buffer.
 	///
 	/// This method appends an HTML end tag "&lt;/tag&gt;" to the formatter's buffer. The provided `tag` is used
@@ -254,6 +478,49 @@ impl<'a> HtmlFormatter<'a> {
 		self.write_bytes(b">");
 	}
 
+	/// Writes an HTML element with the given attributes and content to the formatter's buffer.
+	///
+	/// Void elements are written as a self-closed tag, without the content. The content of the
+	/// `script` and `style` raw text elements is written without escaping entities, though `</`
+	/// is still escaped (as `<\/`) to prevent the content from closing the element. The content
+	/// of other elements, including other raw text elements, is escaped as text.
+	///
+	/// # Arguments
+	///
+	/// - `tag`: A reference to the raw byte slice containing the tag name.
+	/// - `attributes`: The attributes of the element.
+	/// - `content`: The content of the element.
+	///
+	/// # Returns
+	///
+	/// A [std::fmt::Result] indicating the success or failure of the writing operation.
+	pub fn write_element(
+		&mut self,
+		tag: &[u8],
+		attributes: impl HtmlAttributes,
+		content: impl HtmlContent,
+	) -> fmt::Result {
+		self.write_open_tag_start(tag);
+		self.write_attributes(attributes)?;
+
+		if self.elements.is_void_element(tag) {
+			self.write_self_close_tag();
+			return Ok(());
+		}
+
+		self.write_open_tag_end();
+
+		let in_raw_text = self.in_raw_text;
+		self.in_raw_text =
+			self.elements.is_raw_text_element(tag) && elements::is_unescaped_raw_text(tag);
+		let result = self.write_content(content);
+		self.in_raw_text = in_raw_text;
+		result?;
+
+		self.write_end_tag(tag);
+		Ok(())
+	}
+
 	/// Writes HTML content to the formatter's buffer.
 	///
 	/// This method appends HTML content to the formatter's buffer. The provided `content` is an
@@ -271,6 +538,195 @@ impl<'a> HtmlFormatter<'a> {
 		content.fmt(self)
 	}
 
+	/// Writes async content to the formatter's buffer, once it has resolved.
+	///
+	/// The content is deferred, and rendered in place by the [AsyncRender] that is rendering the
+	/// content of the formatter, for instance through [HtmlContent::render_async]. Other
+	/// formatters can't wait for the content to resolve.
+	///
+	/// # Arguments
+	///
+	/// - `content`: An instance implementing the [AsyncHtmlContent] trait, such as a future that resolves to content.
+	///
+	/// # Returns
+	///
+	/// A [std::fmt::Result] indicating the success or failure of the writing operation, which fails
+	/// when the formatter can't defer the content.
+	pub fn write_async_content(&mut self, content: impl AsyncHtmlContent + 'static) -> fmt::Result {
+		if !self.can_defer() {
+			return Err(fmt::Error);
+		}
+
+		let context = self.context.clone();
+		self.defer(Box::pin(async move {
+			let content = content.resolve().await;
+			Box::new(move |formatter: &mut HtmlFormatter| formatter.with_context(context, content))
+				as ResolvedContent
+		}))
+	}
+
+	/// Whether the formatter can defer content that is still being resolved.
+	pub(crate) fn can_defer(&self) -> bool {
+		self.deferred.is_some()
+	}
+
+	/// Defers content that is still being resolved, so it's rendered at the current position once
+	/// it has resolved. Fails when the formatter can't defer content.
+	pub(crate) fn defer(&mut self, content: PendingContent) -> fmt::Result {
+		self.write_open_tag_attributes();
+		let position = self.buffer.len();
+		let deferred = self.deferred.as_mut().ok_or(fmt::Error)?;
+		deferred.push((position, content));
+		Ok(())
+	}
+
+	/// Takes the output written since the state was saved, along with the content deferred since
+	/// then at positions relative to that output. Returns `None` when no content was deferred.
+	pub(crate) fn take_deferred(
+		&mut self,
+		state: &SavedState,
+	) -> Option<(Bytes, Vec<(usize, PendingContent)>)> {
+		if self
+			.deferred
+			.as_ref()
+			.is_none_or(|deferred| deferred.len() <= state.deferred)
+		{
+			return None;
+		}
+
+		self.write_open_tag_attributes();
+		let deferred = self.deferred.as_mut()?;
+		let deferred = deferred
+			.drain(state.deferred..)
+			.map(|(position, content)| (position - state.len, content))
+			.collect();
+
+		Some((self.buffer.split_off(state.len).freeze(), deferred))
+	}
+
+	/// Renders content on its own, with the context and element configuration of the formatter,
+	/// but without deferring content.
+	pub(crate) fn render_separately(
+		&self,
+		content: impl FnOnce(&mut HtmlFormatter) -> fmt::Result,
+	) -> Result<Bytes, fmt::Error> {
+		let mut buffer = BytesMut::new();
+		let mut formatter = HtmlFormatter::with_elements(&mut buffer, self.elements);
+		formatter.context = self.context.clone();
+		content(&mut formatter)?;
+		Ok(buffer.freeze())
+	}
+
+	/// Returns the context value of the given type, if it was provided to the content that is
+	/// being written.
+	///
+	/// ```
+	/// # use rstml_component::{html, write_html, HtmlContent, HtmlFormatter};
+	/// struct Theme(&'static str);
+	///
+	/// let html = html!(<body>{|f: &mut HtmlFormatter| f.provide(Theme("dark"), html!(
+	///     <main>{|f: &mut HtmlFormatter| {
+	///         let theme = f.context::<Theme>().map_or("light", |theme| theme.0);
+	///         write_html!(f, <p class=theme>"Hello"</p>)
+	///     }}</main>
+	/// ))}</body>);
+	///
+	/// assert_eq!(html.into_string().unwrap(), r#"<body><main><p class="dark">Hello</p></main></body>"#);
+	/// ```
+	pub fn context<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+		self.context.get()
+	}
+
+	/// Writes content with a context value, which the content can read through
+	/// [context](Self::context). The value replaces the value of the same type for the content,
+	/// and is removed again once the content is written.
+	///
+	/// # Arguments
+	///
+	/// - `value`: The context value to provide.
+	/// - `content`: An instance implementing the [HtmlContent] trait, representing the HTML content to write.
+	///
+	/// # Returns
+	///
+	/// A [std::fmt::Result] indicating the success or failure of the writing operation.
+	pub fn provide<T: Any + Send + Sync>(
+		&mut self,
+		value: T,
+		content: impl HtmlContent,
+	) -> fmt::Result {
+		let mut context = self.context.clone();
+		context.insert(value);
+		self.with_context(context, content)
+	}
+
+	/// Writes content with the given context values, which replace the current context values
+	/// for the content.
+	///
+	/// # Arguments
+	///
+	/// - `context`: The [ContextStore] with the context values for the content.
+	/// - `content`: An instance implementing the [HtmlContent] trait, representing the HTML content to write.
+	///
+	/// # Returns
+	///
+	/// A [std::fmt::Result] indicating the success or failure of the writing operation.
+	pub fn with_context(&mut self, context: ContextStore, content: impl HtmlContent) -> fmt::Result {
+		let outer = std::mem::replace(&mut self.context, context);
+		let result = content.fmt(self);
+		self.context = outer;
+		result
+	}
+
+	/// Returns the context values of the content that is being written.
+	pub fn context_store(&self) -> &ContextStore {
+		&self.context
+	}
+
+	/// Replaces the state of the innermost [Switch](crate::Switch), which is whether one of its
+	/// cases has matched, or `None` outside of a switch. Returns the previous state.
+	pub(crate) fn replace_switch(&mut self, switch: Option<bool>) -> Option<bool> {
+		std::mem::replace(&mut self.switch, switch)
+	}
+
+	/// Saves the written output and the state of the formatter, so they can be restored by
+	/// [restore](Self::restore) when writing content fails.
+	pub(crate) fn save(&self) -> SavedState {
+		SavedState {
+			len: self.buffer.len(),
+			suspended: self.suspense.as_ref().map_or(0, |suspense| suspense.len()),
+			deferred: self.deferred.as_ref().map_or(0, |deferred| deferred.len()),
+			context: self.context.clone(),
+			in_raw_text: self.in_raw_text,
+			open_tag: self.open_tag.clone(),
+			switch: self.switch,
+		}
+	}
+
+	/// Discards everything written since the state was saved, and restores the state of the
+	/// formatter.
+	pub(crate) fn restore(&mut self, state: SavedState) {
+		self.buffer.truncate(state.len);
+		if let Some(suspense) = &mut self.suspense {
+			suspense.truncate(state.suspended);
+		}
+		if let Some(deferred) = &mut self.deferred {
+			deferred.truncate(state.deferred);
+		}
+		self.context = state.context;
+		self.in_raw_text = state.in_raw_text;
+		self.open_tag = state.open_tag;
+		self.switch = state.switch;
+	}
+
+	/// Adds content that is still being resolved to the suspense queue, returning its id, or
+	/// returns the content back when the formatter has no queue.
+	pub(crate) fn suspend(&mut self, content: PendingContent) -> Result<usize, PendingContent> {
+		match &mut self.suspense {
+			Some(suspense) => Ok(suspense.push(content)),
+			None => Err(content),
+		}
+	}
+
 	/// Writes an HTML comment to the formatter's buffer.
 	///
 	/// This method appends an HTML comment to the formatter's buffer. The provided `comment` is escaped
@@ -293,6 +749,25 @@ impl<'a> HtmlFormatter<'a> {
 		self.write_bytes(COMMENT_SUFFIX);
 	}
 
+	/// Writes an HTML comment with formatted content to the formatter's buffer.
+	///
+	/// The content is escaped the same way as by [write_comment](Self::write_comment), so values
+	/// included in the comment can't end it early.
+	///
+	/// # Arguments
+	///
+	/// - `comment`: The formatted content of the comment, as created by [format_args].
+	///
+	/// # Returns
+	///
+	/// A [std::fmt::Result] indicating the success or failure of the formatting operation.
+	pub fn write_comment_fmt(&mut self, comment: fmt::Arguments) -> fmt::Result {
+		let mut content = Vec::new();
+		display(comment, |value| content.extend_from_slice(value))?;
+		self.write_comment(&content);
+		Ok(())
+	}
+
 	/// Reserves space in the buffer for writing additional bytes without reallocation.
 	///
 	/// This method ensures that enough space is reserved in the formatter's buffer to accommodate
@@ -308,14 +783,32 @@ impl<'a> HtmlFormatter<'a> {
 	}
 }
 
+/// The state of a [HtmlFormatter], created by [HtmlFormatter::save].
+pub(crate) struct SavedState {
+	len: usize,
+	suspended: usize,
+	deferred: usize,
+	context: ContextStore,
+	in_raw_text: bool,
+	open_tag: Option<Vec<OpenTagAttribute>>,
+	switch: Option<bool>,
+}
+
+/// An attribute of an open tag, which is written when the tag is closed.
+#[derive(Clone)]
+struct OpenTagAttribute {
+	name: Vec<u8>,
+	/// The escaped value, without the quotes, or `None` for an attribute without a value.
+	value: Option<BytesMut>,
+}
+
 pub struct HtmlAttributesFormatter<'a, 'b> {
 	inner: &'a mut HtmlFormatter<'b>,
 }
 
 impl<'a, 'b> HtmlAttributesFormatter<'a, 'b> {
 	pub fn write_attribute(&mut self, name: &[u8], value: impl HtmlAttributeValue) -> fmt::Result {
-		self.inner.write_attribute_name(name);
-		self.inner.write_attribute_value(value)
+		self.inner.write_attribute(name, value)
 	}
 }
 
@@ -384,6 +877,29 @@ pub trait HtmlContent: Sized {
 		let bytes = self.into_bytes()?;
 		String::from_utf8(bytes.to_vec()).map_err(|_| fmt::Error)
 	}
+
+	/// Renders the content, deferring the async content it contains. The content of
+	/// [Suspense](crate::Suspense) components is awaited in place like other async content.
+	///
+	/// # Returns
+	///
+	/// A [Result] containing the [AsyncRender] that renders the async content once it has
+	/// resolved, or a [std::fmt::Error] if formatting fails.
+	fn render_async(self) -> Result<AsyncRender, fmt::Error> {
+		AsyncRender::new(self)
+	}
+
+	/// Renders the content, deferring the async content it contains. The fallbacks of
+	/// [Suspense](crate::Suspense) components are rendered in place, and their content is
+	/// streamed as chunks after the rest of the output, as with a [SuspenseQueue].
+	///
+	/// # Returns
+	///
+	/// A [Result] containing the [AsyncRender] that renders the async content once it has
+	/// resolved, or a [std::fmt::Error] if formatting fails.
+	fn render_streaming(self) -> Result<AsyncRender, fmt::Error> {
+		AsyncRender::streaming(self)
+	}
 }
 
 pub trait HtmlAttributes {
@@ -410,6 +926,13 @@ pub trait HtmlAttributeValue {
 	///
 	/// A [std::fmt::Result] indicating the success or failure of the formatting operation.
 	fn fmt(self, formatter: &mut HtmlAttributeFormatter) -> fmt::Result;
+
+	/// Returns whether the attribute should be written at all. When this returns `false`, the
+	/// attribute is left out entirely, rather than being written without a value. Defaults to
+	/// `true`; `None` is not present.
+	fn is_present(&self) -> bool {
+		true
+	}
 }
 
 /// A struct for embedding raw, unsanitized HTML content.
@@ -449,6 +972,88 @@ impl<V: AsRef<[u8]>> HtmlAttributeValue for RawText<V> {
 	}
 }
 
+/// The output of a template without any dynamic values, created by the `static_html!` macro.
+///
+/// The HTML is escaped while expanding the template, so it can be written without any runtime
+/// cost, and is available in `const` contexts:
+///
+/// ```
+/// # use rstml_component::{static_html, HtmlContent, StaticHtml};
+/// const NOT_FOUND: StaticHtml = static_html!(<h1>"Not found"</h1>);
+/// const LEN: usize = NOT_FOUND.len();
+///
+/// assert_eq!(LEN, 18);
+/// assert_eq!(NOT_FOUND.into_string().unwrap(), "<h1>Not found</h1>");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StaticHtml(&'static str);
+
+impl StaticHtml {
+	/// Creates a `StaticHtml` from HTML that is already escaped. Like [RawText], the HTML is
+	/// written as is.
+	pub const fn from_raw(html: &'static str) -> Self {
+		Self(html)
+	}
+
+	/// Returns the HTML as a string.
+	pub const fn as_str(&self) -> &'static str {
+		self.0
+	}
+
+	/// Returns the HTML as bytes.
+	pub const fn as_bytes(&self) -> &'static [u8] {
+		self.0.as_bytes()
+	}
+
+	/// Returns the length of the HTML in bytes.
+	pub const fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	/// Returns whether the HTML is empty.
+	pub const fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+}
+
+impl HtmlContent for StaticHtml {
+	fn fmt(self, formatter: &mut HtmlFormatter) -> fmt::Result {
+		formatter.write_bytes(self.as_bytes());
+		Ok(())
+	}
+
+	/// Returns the HTML without copying it, using [Bytes::from_static].
+	fn into_bytes(self) -> Result<Bytes, fmt::Error> {
+		Ok(Bytes::from_static(self.as_bytes()))
+	}
+
+	fn into_string(self) -> Result<String, fmt::Error> {
+		Ok(self.0.to_owned())
+	}
+
+	fn render_async(self) -> Result<AsyncRender, fmt::Error> {
+		Ok(AsyncRender::from_bytes(self.into_bytes()?))
+	}
+}
+
+impl From<StaticHtml> for Bytes {
+	fn from(value: StaticHtml) -> Self {
+		Bytes::from_static(value.as_bytes())
+	}
+}
+
+impl AsRef<str> for StaticHtml {
+	fn as_ref(&self) -> &str {
+		self.0
+	}
+}
+
+impl AsRef<[u8]> for StaticHtml {
+	fn as_ref(&self) -> &[u8] {
+		self.as_bytes()
+	}
+}
+
 impl<F> HtmlContent for F
 where
 	F: FnOnce(&mut HtmlFormatter) -> fmt::Result,
@@ -458,6 +1063,15 @@ where
 	}
 }
 
+impl<F> HtmlAttributes for F
+where
+	F: FnOnce(&mut HtmlAttributesFormatter) -> fmt::Result,
+{
+	fn fmt(self, formatter: &mut HtmlAttributesFormatter) -> fmt::Result {
+		self(formatter)
+	}
+}
+
 impl HtmlContent for () {
 	fn fmt(self, _formatter: &mut HtmlFormatter) -> fmt::Result {
 		Ok(())
@@ -492,6 +1106,10 @@ impl<T: HtmlAttributeValue> HtmlAttributeValue for Option<T> {
 			Some(template) => template.fmt(formatter),
 		}
 	}
+
+	fn is_present(&self) -> bool {
+		self.as_ref().is_some_and(HtmlAttributeValue::is_present)
+	}
 }
 
 impl<T: HtmlAttributes> HtmlAttributes for Option<T> {
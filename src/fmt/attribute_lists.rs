@@ -0,0 +1,149 @@
+use super::{HtmlAttributeFormatter, HtmlAttributeValue};
+use bytes::{BufMut, BytesMut};
+use std::fmt;
+
+/// The value of a `class` attribute, built from class names that are added conditionally.
+///
+/// Templates use this to merge `class` attributes with `class:name={condition}` directives:
+///
+/// ```
+/// # use rstml_component::{html, HtmlContent};
+/// let active = true;
+/// let html = html!(<a class="link" class:active={active} class:disabled={false} />);
+/// assert_eq!(html.into_string().unwrap(), r#"<a class="link active"></a>"#);
+/// ```
+///
+/// When no class is added, the attribute is left out entirely.
+#[derive(Debug, Default, Clone)]
+pub struct Classes {
+	value: BytesMut,
+}
+
+impl Classes {
+	/// Creates an empty list of classes.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds one or more space separated classes. Values that are not present, such as `None`,
+	/// are skipped.
+	pub fn with(mut self, classes: impl HtmlAttributeValue) -> Result<Self, fmt::Error> {
+		if classes.is_present() {
+			let classes = HtmlAttributeFormatter::escaped_value(classes)?;
+			append(&mut self.value, &classes, b" ");
+		}
+
+		Ok(self)
+	}
+
+	/// Adds a class if the condition is `true`.
+	pub fn with_if(
+		self,
+		class: impl HtmlAttributeValue,
+		condition: bool,
+	) -> Result<Self, fmt::Error> {
+		if condition {
+			self.with(class)
+		} else {
+			Ok(self)
+		}
+	}
+}
+
+impl HtmlAttributeValue for Classes {
+	fn fmt(self, formatter: &mut HtmlAttributeFormatter) -> fmt::Result {
+		formatter.write_bytes(&self.value);
+		Ok(())
+	}
+
+	fn is_present(&self) -> bool {
+		!self.value.is_empty()
+	}
+}
+
+/// The value of a `style` attribute, built from declarations that are added conditionally.
+///
+/// Templates use this to merge `style` attributes with `style:property={value}` directives:
+///
+/// ```
+/// # use rstml_component::{html, HtmlContent};
+/// let color = Some("red");
+/// let html = html!(<p style="margin: 0" style:color={color} style:width={None::<&str>} />);
+/// assert_eq!(html.into_string().unwrap(), r#"<p style="margin: 0; color: red"></p>"#);
+/// ```
+///
+/// When no declaration is added, the attribute is left out entirely.
+#[derive(Debug, Default, Clone)]
+pub struct Styles {
+	value: BytesMut,
+}
+
+impl Styles {
+	/// Creates an empty list of declarations.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds one or more declarations, such as `"margin: 0; padding: 0"`. Values that are not
+	/// present, such as `None`, are skipped.
+	pub fn with(mut self, declarations: impl HtmlAttributeValue) -> Result<Self, fmt::Error> {
+		if declarations.is_present() {
+			let declarations = HtmlAttributeFormatter::escaped_value(declarations)?;
+			let declarations = trim_end(&declarations, b"; ");
+			append(&mut self.value, declarations, b"; ");
+		}
+
+		Ok(self)
+	}
+
+	/// Adds the declaration `property: value`, unless the value is not present.
+	pub fn property(
+		mut self,
+		property: &str,
+		value: impl HtmlAttributeValue,
+	) -> Result<Self, fmt::Error> {
+		if value.is_present() {
+			let value = HtmlAttributeFormatter::escaped_value(value)?;
+			let mut declaration = BytesMut::with_capacity(property.len() + value.len() + 2);
+			declaration.put_slice(property.as_bytes());
+			declaration.put_slice(b": ");
+			declaration.put_slice(&value);
+			append(&mut self.value, &declaration, b"; ");
+		}
+
+		Ok(self)
+	}
+}
+
+impl HtmlAttributeValue for Styles {
+	fn fmt(self, formatter: &mut HtmlAttributeFormatter) -> fmt::Result {
+		formatter.write_bytes(&self.value);
+		Ok(())
+	}
+
+	fn is_present(&self) -> bool {
+		!self.value.is_empty()
+	}
+}
+
+fn append(value: &mut BytesMut, item: &[u8], separator: &[u8]) {
+	let item = item.trim_ascii();
+	if item.is_empty() {
+		return;
+	}
+
+	if !value.is_empty() {
+		value.put_slice(separator);
+	}
+
+	value.put_slice(item);
+}
+
+fn trim_end<'a>(value: &'a [u8], chars: &[u8]) -> &'a [u8] {
+	let end = value
+		.iter()
+		.rposition(|ch| !chars.contains(ch))
+		.map_or(0, |i| i + 1);
+
+	&value[..end]
+}
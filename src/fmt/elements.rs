@@ -0,0 +1,122 @@
+use std::borrow::Cow;
+
+/// The elements that are formatted differently from other elements.
+///
+/// Void elements can't have content, and are written as self-closed tags. The content of the
+/// `script` and `style` raw text elements is not entity-escaped, as browsers don't decode
+/// character references in it. Browsers do decode them in other elements, including `textarea`
+/// and `title`, so the content of other raw text elements is escaped like any other text.
+///
+/// The defaults match the elements the `html!` and `write_html!` macros treat as void and raw
+/// text elements. A template that changes these sets using `#![html(void_elements(...))]` or
+/// `#![html(raw_text_elements(...))]` writes its content with the changed sets, through
+/// [HtmlFormatter::write_with_elements](crate::HtmlFormatter::write_with_elements).
+#[derive(Debug, Clone)]
+pub struct ElementConfig {
+	void_elements: Cow<'static, [&'static str]>,
+	raw_text_elements: Cow<'static, [&'static str]>,
+}
+
+/// The configuration used by formatters that are not given one explicitly.
+pub(crate) static HTML_ELEMENTS: ElementConfig = ElementConfig::html();
+
+impl ElementConfig {
+	/// Creates the configuration for HTML, as defined by the HTML standard.
+	pub const fn html() -> Self {
+		Self::new(
+			&[
+				"area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param",
+				"source", "track", "wbr",
+			],
+			&["script", "style"],
+		)
+	}
+
+	/// Creates a configuration with the given void and raw text elements.
+	pub const fn new(
+		void_elements: &'static [&'static str],
+		raw_text_elements: &'static [&'static str],
+	) -> Self {
+		Self {
+			void_elements: Cow::Borrowed(void_elements),
+			raw_text_elements: Cow::Borrowed(raw_text_elements),
+		}
+	}
+
+	/// Adds elements to the set of void elements.
+	pub fn with_void_elements(mut self, elements: impl IntoIterator<Item = &'static str>) -> Self {
+		extend(&mut self.void_elements, elements);
+		self
+	}
+
+	/// Removes elements from the set of void elements.
+	pub fn without_void_elements(mut self, elements: impl IntoIterator<Item = &'static str>) -> Self {
+		remove(&mut self.void_elements, elements);
+		self
+	}
+
+	/// Adds elements to the set of raw text elements.
+	pub fn with_raw_text_elements(
+		mut self,
+		elements: impl IntoIterator<Item = &'static str>,
+	) -> Self {
+		extend(&mut self.raw_text_elements, elements);
+		self
+	}
+
+	/// Removes elements from the set of raw text elements.
+	pub fn without_raw_text_elements(
+		mut self,
+		elements: impl IntoIterator<Item = &'static str>,
+	) -> Self {
+		remove(&mut self.raw_text_elements, elements);
+		self
+	}
+
+	/// Returns whether the element is a void element.
+	pub fn is_void_element(&self, tag: &[u8]) -> bool {
+		contains(&self.void_elements, tag)
+	}
+
+	/// Returns whether the element is a raw text element.
+	pub fn is_raw_text_element(&self, tag: &[u8]) -> bool {
+		contains(&self.raw_text_elements, tag)
+	}
+}
+
+impl Default for ElementConfig {
+	fn default() -> Self {
+		Self::html()
+	}
+}
+
+/// Returns whether the content of the element isn't entity-escaped when it's a raw text element.
+pub(crate) fn is_unescaped_raw_text(tag: &[u8]) -> bool {
+	matches!(tag, b"script" | b"style")
+}
+
+fn contains(elements: &[&str], tag: &[u8]) -> bool {
+	elements.iter().any(|element| element.as_bytes() == tag)
+}
+
+fn extend(
+	elements: &mut Cow<'static, [&'static str]>,
+	new: impl IntoIterator<Item = &'static str>,
+) {
+	for element in new {
+		if !contains(elements, element.as_bytes()) {
+			elements.to_mut().push(element);
+		}
+	}
+}
+
+fn remove(
+	elements: &mut Cow<'static, [&'static str]>,
+	old: impl IntoIterator<Item = &'static str>,
+) {
+	for element in old {
+		if contains(elements, element.as_bytes()) {
+			elements.to_mut().retain(|e| *e != element);
+		}
+	}
+}
@@ -33,6 +33,26 @@ pub fn text(raw: &[u8]) -> Cow<'_, [u8]> {
 	_escape(raw, |ch| matches!(ch, b'<' | b'>' | b'&'))
 }
 
+/// Escapes the content of the `script` and `style` raw text elements, which are not parsed as
+/// HTML, so only `</` needs to be escaped (as `<\/`) to prevent the content from closing the
+/// element.
+pub fn raw_text(raw: &[u8]) -> Cow<'_, [u8]> {
+	if !raw.windows(2).any(|window| window == b"</") {
+		return Cow::Borrowed(raw);
+	}
+
+	let mut escaped = Vec::with_capacity(raw.len() + 8);
+	let mut pos = 0;
+	while let Some(i) = raw[pos..].windows(2).position(|window| window == b"</") {
+		escaped.extend_from_slice(&raw[pos..pos + i]);
+		escaped.extend_from_slice(b"<\\/");
+		pos += i + 2;
+	}
+
+	escaped.extend_from_slice(&raw[pos..]);
+	Cow::Owned(escaped)
+}
+
 /// Escapes an `&str` and replaces a subset of xml special characters (`<`, `>`,
 /// `&`, `'`, `"`) with their corresponding xml escaped value.
 pub(crate) fn _escape<F: Fn(u8) -> bool>(bytes: &[u8], escape_chars: F) -> Cow<'_, [u8]> {
@@ -2,20 +2,37 @@
 // the `docsrs` configuration attribute is defined
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+// allows the derive macros to be used within this crate
+extern crate self as rstml_component;
+
+mod async_content;
 mod builtins;
 mod component;
+mod context;
 mod fmt;
+mod render;
+mod suspense;
 
 #[cfg(feature = "sanitize")]
 mod sanitize;
 
-pub use builtins::For;
+pub use async_content::{AsyncHtmlContent, AsyncRender};
+pub use builtins::{
+	CaughtError, Condition, ErrorBoundary, ErrorBoundaryBuilder, ErrorFallback, ErrorHook, For,
+	ForBuilder, Iteration, Iterations, Match, Provider, Show, ShowBuilder, Switch, SwitchBuilder,
+};
 pub use component::HtmlComponent;
+pub use context::{ContextStore, FromContext};
 pub use fmt::{
-	HtmlAttributeFormatter, HtmlAttributeValue, HtmlAttributes, HtmlAttributesFormatter, HtmlContent,
-	HtmlFormatter, RawText,
+	Classes, ElementConfig, HtmlAttributeFormatter, HtmlAttributeValue, HtmlAttributes,
+	HtmlAttributesFormatter, HtmlContent, HtmlFormatter, RawText, StaticHtml, Styles,
+};
+pub use render::{HtmlRender, RenderRef};
+pub use rstml_component_macro::{
+	component, html, html_ref, static_html, write_html, HtmlAttributeValue, HtmlAttributes,
+	HtmlComponent, HtmlContent,
 };
-pub use rstml_component_macro::{component, html, write_html, HtmlComponent};
+pub use suspense::{Suspense, SuspenseBuilder, SuspenseQueue};
 
 #[cfg(feature = "sanitize")]
 #[cfg_attr(docsrs, doc(cfg(feature = "sanitize")))]
@@ -0,0 +1,151 @@
+use crate::{HtmlContent, HtmlFormatter, StaticHtml};
+use bytes::Bytes;
+use std::fmt;
+
+/// A trait representing content that can be rendered any number of times.
+///
+/// [HtmlContent] consumes the content when formatting it, so a value can only be written once.
+/// Types that implement this trait are rendered by reference instead, which allows a template or
+/// a component to be rendered for every row of a table, or in several places of a page, without
+/// cloning the data it refers to.
+///
+/// Templates created with the `html_ref!` macro implement this trait, as long as they only
+/// borrow the values they capture (for instance `{&self.title}` rather than `{self.title}`).
+pub trait HtmlRender {
+	/// Renders the content to the provided [HtmlFormatter].
+	///
+	/// # Arguments
+	///
+	/// - `formatter`: A mutable reference to the [HtmlFormatter] that handles the output.
+	///
+	/// # Returns
+	///
+	/// A [std::fmt::Result] indicating the success or failure of the formatting operation.
+	fn render(&self, formatter: &mut HtmlFormatter) -> fmt::Result;
+
+	/// Returns [HtmlContent] that renders this value by reference, so it can be used anywhere
+	/// content is expected, such as in a template or as the children of a component.
+	fn as_content(&self) -> RenderRef<'_, Self> {
+		RenderRef(self)
+	}
+
+	/// Renders the content into a [Bytes] buffer.
+	fn render_to_bytes(&self) -> Result<Bytes, fmt::Error> {
+		self.as_content().into_bytes()
+	}
+
+	/// Renders the content into a [String].
+	fn render_to_string(&self) -> Result<String, fmt::Error> {
+		self.as_content().into_string()
+	}
+}
+
+/// [HtmlContent] that renders a value implementing [HtmlRender] by reference, as returned by
+/// [HtmlRender::as_content].
+pub struct RenderRef<'a, T: ?Sized>(&'a T);
+
+impl<'a, T: HtmlRender + ?Sized> HtmlContent for RenderRef<'a, T> {
+	fn fmt(self, formatter: &mut HtmlFormatter) -> fmt::Result {
+		self.0.render(formatter)
+	}
+}
+
+impl<'a, T: ?Sized> Clone for RenderRef<'a, T> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<'a, T: ?Sized> Copy for RenderRef<'a, T> {}
+
+impl<F> HtmlRender for F
+where
+	F: Fn(&mut HtmlFormatter) -> fmt::Result,
+{
+	fn render(&self, formatter: &mut HtmlFormatter) -> fmt::Result {
+		self(formatter)
+	}
+}
+
+impl HtmlRender for () {
+	fn render(&self, _formatter: &mut HtmlFormatter) -> fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: HtmlRender> HtmlRender for Option<T> {
+	fn render(&self, formatter: &mut HtmlFormatter) -> fmt::Result {
+		match self {
+			None => Ok(()),
+			Some(value) => value.render(formatter),
+		}
+	}
+}
+
+impl<T: HtmlRender> HtmlContent for &Option<T> {
+	fn fmt(self, formatter: &mut HtmlFormatter) -> fmt::Result {
+		self.render(formatter)
+	}
+}
+
+impl HtmlRender for StaticHtml {
+	fn render(&self, formatter: &mut HtmlFormatter) -> fmt::Result {
+		HtmlContent::fmt(*self, formatter)
+	}
+}
+
+impl<'a> HtmlRender for fmt::Arguments<'a> {
+	fn render(&self, formatter: &mut HtmlFormatter) -> fmt::Result {
+		HtmlContent::fmt(*self, formatter)
+	}
+}
+
+macro_rules! impl_simple_render {
+	($ty:ty, as_ref) => {
+		impl HtmlRender for $ty {
+			fn render(&self, formatter: &mut HtmlFormatter) -> fmt::Result {
+				formatter.write(self.as_ref());
+				Ok(())
+			}
+		}
+	};
+	($ty:ty, copy) => {
+		impl HtmlRender for $ty {
+			fn render(&self, formatter: &mut HtmlFormatter) -> fmt::Result {
+				HtmlContent::fmt(*self, formatter)
+			}
+		}
+
+		impl HtmlContent for &$ty {
+			fn fmt(self, formatter: &mut HtmlFormatter) -> fmt::Result {
+				HtmlContent::fmt(*self, formatter)
+			}
+		}
+	};
+}
+
+impl_simple_render!(str, as_ref);
+impl_simple_render!(&str, as_ref);
+impl_simple_render!(String, as_ref);
+impl_simple_render!(Bytes, as_ref);
+impl_simple_render!(bool, copy);
+impl_simple_render!(u8, copy);
+impl_simple_render!(u16, copy);
+impl_simple_render!(u32, copy);
+impl_simple_render!(u64, copy);
+impl_simple_render!(u128, copy);
+impl_simple_render!(usize, copy);
+impl_simple_render!(i8, copy);
+impl_simple_render!(i16, copy);
+impl_simple_render!(i32, copy);
+impl_simple_render!(i64, copy);
+impl_simple_render!(i128, copy);
+impl_simple_render!(isize, copy);
+impl_simple_render!(f32, copy);
+impl_simple_render!(f64, copy);
+
+impl HtmlContent for &Bytes {
+	fn fmt(self, formatter: &mut HtmlFormatter) -> fmt::Result {
+		self.render(formatter)
+	}
+}
@@ -0,0 +1,267 @@
+use crate::{async_content::Segments, HtmlComponent, HtmlContent, HtmlFormatter};
+use bytes::Bytes;
+use std::{
+	fmt,
+	future::{self, Future},
+	mem,
+	pin::Pin,
+	task::{Context, Poll},
+};
+
+/// Content that has resolved, and is rendered as a chunk.
+pub(crate) type ResolvedContent = Box<dyn FnOnce(&mut HtmlFormatter) -> fmt::Result + Send>;
+
+/// Content that is still being resolved.
+pub(crate) type PendingContent = Pin<Box<dyn Future<Output = ResolvedContent> + Send>>;
+
+/// Renders content that is resolved by a future, without waiting for it.
+///
+/// - `children`: The future that resolves the content, for instance an `async` block.
+/// - `fallback` (optional): Content rendered in place until the content has resolved.
+///
+/// When the formatter has a [SuspenseQueue], the fallback is written in a placeholder element
+/// and the rest of the page is rendered. Once the future resolves, the queue writes the content
+/// as a `<template>` chunk after the page, with an inline script that swaps it into the
+/// placeholder. Chunks are written in the order their futures resolve.
+///
+/// Without a queue, formatters that defer async content, such as the one used by
+/// [HtmlContent::render_async], await the content in place like other async content. Other
+/// formatters, as for [HtmlContent::into_string], only render the fallback.
+///
+/// ```
+/// # use rstml_component::{html, HtmlContent, HtmlFormatter, Suspense, SuspenseQueue};
+/// # use bytes::BytesMut;
+/// async fn recommendations() -> Vec<&'static str> {
+///     vec!["Dune", "Solaris"]
+/// }
+///
+/// let page = html!(
+///     <main>
+///         <Suspense fallback={html!(<p>"Loading..."</p>)}>
+///             {async {
+///                 let books = recommendations().await;
+///                 html!(<p>{books.join(", ")}</p>)
+///             }}
+///         </Suspense>
+///     </main>
+/// );
+///
+/// let mut buffer = BytesMut::new();
+/// let mut queue = SuspenseQueue::new();
+/// page.fmt(&mut HtmlFormatter::with_suspense(&mut buffer, &mut queue)).unwrap();
+///
+/// assert_eq!(
+///     buffer,
+///     r#"<main><rstml-suspense id="rstml-suspense-0"><p>Loading...</p></rstml-suspense></main>"#,
+/// );
+/// assert_eq!(queue.len(), 1);
+/// ```
+pub struct Suspense<C, F = ()> {
+	pub children: C,
+	pub fallback: F,
+}
+
+impl Suspense<(), ()> {
+	/// Creates a builder for [Suspense], where `children` must be set.
+	pub fn builder() -> SuspenseBuilder<(), ()> {
+		SuspenseBuilder(Suspense {
+			children: (),
+			fallback: (),
+		})
+	}
+}
+
+/// A builder for [Suspense], created by [Suspense::builder].
+pub struct SuspenseBuilder<C, F>(Suspense<C, F>);
+
+impl<C, F> SuspenseBuilder<C, F> {
+	pub fn children<T>(self, children: T) -> SuspenseBuilder<T, F> {
+		let Suspense { fallback, .. } = self.0;
+
+		SuspenseBuilder(Suspense { children, fallback })
+	}
+
+	pub fn fallback<T>(self, fallback: T) -> SuspenseBuilder<C, T> {
+		let Suspense { children, .. } = self.0;
+
+		SuspenseBuilder(Suspense { children, fallback })
+	}
+}
+
+impl<C, F> SuspenseBuilder<C, F>
+where
+	C: Future + Send + 'static,
+	C::Output: HtmlContent + Send,
+	F: HtmlContent,
+{
+	pub fn build(self) -> Suspense<C, F> {
+		self.0
+	}
+}
+
+impl<C, F> HtmlComponent for Suspense<C, F>
+where
+	C: Future + Send + 'static,
+	C::Output: HtmlContent + Send,
+	F: HtmlContent,
+{
+	type Content = Self;
+
+	fn into_content(self) -> Self::Content {
+		self
+	}
+}
+
+impl<C, F> HtmlContent for Suspense<C, F>
+where
+	C: Future + Send + 'static,
+	C::Output: HtmlContent + Send,
+	F: HtmlContent,
+{
+	fn fmt(self, formatter: &mut HtmlFormatter) -> fmt::Result {
+		let Suspense { children, fallback } = self;
+
+		let context = formatter.context_store().clone();
+		let content = Box::pin(async move {
+			let content = children.await;
+			Box::new(move |formatter: &mut HtmlFormatter| formatter.with_context(context, content))
+				as ResolvedContent
+		});
+
+		let id = match formatter.suspend(content) {
+			Ok(id) => id,
+			Err(content) if formatter.can_defer() => return formatter.defer(content),
+			Err(_) => return formatter.write_content(fallback),
+		};
+
+		formatter.write_bytes(b"<rstml-suspense id=\"rstml-suspense-");
+		formatter.write_bytes(id.to_string().as_bytes());
+		formatter.write_bytes(b"\">");
+		formatter.write_content(fallback)?;
+		formatter.write_bytes(b"</rstml-suspense>");
+		Ok(())
+	}
+}
+
+/// The content of the [Suspense] components of a page that is still being resolved, given to
+/// the formatter by [HtmlFormatter::with_suspense].
+///
+/// After the page is rendered, [poll_chunk](Self::poll_chunk) drives the pending futures and
+/// renders the content of each as a chunk once it resolves. Content that contains [Suspense]
+/// components itself adds them to the queue, and async content in a chunk is awaited in place
+/// before the rest of the chunk is returned.
+#[derive(Default)]
+pub struct SuspenseQueue {
+	next_id: usize,
+	pending: Vec<(usize, PendingContent)>,
+	/// The chunk that is being returned.
+	chunk: Segments,
+}
+
+impl SuspenseQueue {
+	/// Creates an empty queue.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The number of [Suspense] components whose content is still being resolved.
+	pub fn len(&self) -> usize {
+		self.pending.len()
+	}
+
+	/// Whether all content has resolved and been rendered.
+	pub fn is_empty(&self) -> bool {
+		self.pending.is_empty() && self.chunk.is_empty()
+	}
+
+	/// Polls the pending content, and renders the first that has resolved as a chunk, which
+	/// replaces the placeholder of its [Suspense] component when it's added to the page.
+	///
+	/// # Returns
+	///
+	/// - `Poll::Ready(Some(chunk))` with the next part of a chunk, where rendering it may have
+	///   failed. A chunk is returned in several parts when it contains async content.
+	/// - `Poll::Ready(None)` when all content has been rendered.
+	/// - `Poll::Pending` when content is still being resolved. The waker of the context is woken
+	///   when it should be polled again.
+	pub fn poll_chunk(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes, fmt::Error>>> {
+		loop {
+			let mut chunk = mem::take(&mut self.chunk);
+			let poll = chunk.poll_next(cx, false, Some(self));
+			self.chunk = chunk;
+			match poll {
+				Poll::Ready(None) => {}
+				Poll::Pending => {
+					self.poll_pending(cx);
+					return Poll::Pending;
+				}
+				poll => return poll,
+			}
+
+			if self.pending.is_empty() {
+				return Poll::Ready(None);
+			}
+
+			let resolved = self
+				.pending
+				.iter_mut()
+				.enumerate()
+				.find_map(|(index, (id, content))| match content.as_mut().poll(cx) {
+					Poll::Ready(content) => Some((index, *id, content)),
+					Poll::Pending => None,
+				});
+
+			let Some((index, id, content)) = resolved else {
+				return Poll::Pending;
+			};
+
+			drop(self.pending.remove(index));
+			match self.render_chunk(id, content) {
+				Ok(chunk) => self.chunk = chunk,
+				Err(error) => return Poll::Ready(Some(Err(error))),
+			}
+		}
+	}
+
+	/// Polls the pending content without rendering it, so it's resolved while other output is
+	/// still being written.
+	pub(crate) fn poll_pending(&mut self, cx: &mut Context<'_>) {
+		for (_, content) in &mut self.pending {
+			if let Poll::Ready(resolved) = content.as_mut().poll(cx) {
+				*content = Box::pin(future::ready(resolved));
+			}
+		}
+	}
+
+	fn render_chunk(&mut self, id: usize, content: ResolvedContent) -> Result<Segments, fmt::Error> {
+		let id = id.to_string();
+		let chunk = |formatter: &mut HtmlFormatter| {
+			formatter.write_bytes(b"<template id=\"rstml-suspense-content-");
+			formatter.write_bytes(id.as_bytes());
+			formatter.write_bytes(b"\">");
+			content(formatter)?;
+			formatter.write_bytes(
+				b"</template><script>(function(){var p=document.getElementById(\"rstml-suspense-",
+			);
+			formatter.write_bytes(id.as_bytes());
+			formatter.write_bytes(b"\"),t=document.getElementById(\"rstml-suspense-content-");
+			formatter.write_bytes(id.as_bytes());
+			formatter.write_bytes(b"\");if(p)p.replaceWith(t.content);t.remove();})()</script>");
+			Ok(())
+		};
+
+		Segments::render(chunk, Some(self))
+	}
+
+	pub(crate) fn push(&mut self, content: PendingContent) -> usize {
+		let id = self.next_id;
+		self.next_id += 1;
+		self.pending.push((id, content));
+		id
+	}
+
+	/// Drops the content that was added after the queue had the given length.
+	pub(crate) fn truncate(&mut self, len: usize) {
+		self.pending.truncate(len);
+	}
+}
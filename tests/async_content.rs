@@ -0,0 +1,276 @@
+use rstml_component::{
+	component, html, html_ref, write_html, AsyncHtmlContent, ErrorBoundary, HtmlContent,
+	HtmlFormatter, Suspense,
+};
+use std::{
+	cell::RefCell,
+	future::Future,
+	pin::pin,
+	sync::{Arc, Mutex},
+	task::{Context, Poll, Waker},
+};
+
+/// A future that is pending for the given number of polls, and then records its name.
+struct Delay {
+	polls: usize,
+	name: &'static str,
+	log: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl Future for Delay {
+	type Output = &'static str;
+
+	fn poll(mut self: std::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		if self.polls == 0 {
+			self.log.lock().unwrap().push(self.name);
+			return Poll::Ready(self.name);
+		}
+
+		self.polls -= 1;
+		cx.waker().wake_by_ref();
+		Poll::Pending
+	}
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+	let mut future = pin!(future);
+	let mut cx = Context::from_waker(Waker::noop());
+	loop {
+		if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+			return output;
+		}
+	}
+}
+
+#[component(Item)]
+async fn item(
+	name: &'static str,
+	polls: usize,
+	log: Arc<Mutex<Vec<&'static str>>>,
+) -> impl HtmlContent {
+	let name = Delay { polls, name, log }.await;
+	html!(<li>{name}</li>)
+}
+
+#[test]
+fn async_components_in_document_order() {
+	let log = Arc::new(Mutex::new(Vec::new()));
+	let html = {
+		let log = log.clone();
+		async move {
+			html!(
+				<ul>
+					<Item name="slow" polls={3} log={log.clone()} />
+					<Item name="fast" polls={1} log={log} />
+				</ul>
+			)
+		}
+	};
+
+	assert_eq!(
+		block_on(html.into_string_async()).unwrap(),
+		"<ul><li>slow</li><li>fast</li></ul>"
+	);
+	assert_eq!(*log.lock().unwrap(), ["slow", "fast"]);
+}
+
+#[test]
+fn async_components_concurrently() {
+	let log = Arc::new(Mutex::new(Vec::new()));
+	let render = html_ref!(
+		<ul>
+			<Item name="slow" polls={3} log={log.clone()} />
+			<Item name="fast" polls={1} log={log.clone()} />
+		</ul>
+	)
+	.render_async()
+	.unwrap()
+	.concurrent(true);
+
+	let bytes = block_on(render.into_bytes()).unwrap();
+	assert_eq!(bytes, "<ul><li>slow</li><li>fast</li></ul>");
+	assert_eq!(*log.lock().unwrap(), ["fast", "slow"]);
+}
+
+#[component(Nested)]
+async fn nested(log: Arc<Mutex<Vec<&'static str>>>) -> impl HtmlContent {
+	html!(
+		<section>
+			<Item name="inner" polls={1} log={log} />
+		</section>
+	)
+}
+
+#[test]
+fn nested_async_components() {
+	let log = Arc::new(Mutex::new(Vec::new()));
+	let html = html!(<main><Nested log={log.clone()} /><footer /></main>);
+
+	assert_eq!(
+		block_on(html.render_async().unwrap().into_bytes()).unwrap(),
+		"<main><section><li>inner</li></section><footer></footer></main>"
+	);
+}
+
+#[test]
+fn rendered_without_async_content() {
+	let render = html!(<p>"static"</p>).render_async().unwrap();
+	assert_eq!(render.into_rendered().ok().unwrap(), "<p>static</p>");
+}
+
+#[test]
+fn async_content_requires_async_render() {
+	let log = Arc::new(Mutex::new(Vec::new()));
+	let html = html!(<Item name="item" polls={0} log={log} />);
+
+	assert!(html.into_string().is_err());
+}
+
+#[test]
+fn async_content_discarded_by_error_boundary() {
+	fn broken(_: &mut HtmlFormatter) -> std::fmt::Result {
+		Err(std::fmt::Error)
+	}
+
+	let log = Arc::new(Mutex::new(Vec::new()));
+	let errors = RefCell::new(0);
+	let html = html_ref!(
+		<ErrorBoundary
+			fallback={|f, _| write_html!(f, <p>"failed"</p>)}
+			on_error={|_| *errors.borrow_mut() += 1}
+		>
+			<Item name="discarded" polls={0} log={log.clone()} />
+			{broken}
+		</ErrorBoundary>
+	);
+
+	assert_eq!(
+		block_on(html.render_async().unwrap().into_bytes()).unwrap(),
+		"<p>failed</p>"
+	);
+	assert_eq!(errors.into_inner(), 1);
+	assert!(log.lock().unwrap().is_empty());
+}
+
+#[component(Broken)]
+async fn broken() -> impl HtmlContent {
+	fn broken(_: &mut HtmlFormatter) -> std::fmt::Result {
+		Err(std::fmt::Error)
+	}
+
+	html!(<p>{broken}</p>)
+}
+
+#[test]
+fn async_content_in_error_boundary() {
+	let log = Arc::new(Mutex::new(Vec::new()));
+	let html = html!(
+		<main>
+			<ErrorBoundary fallback={|f, _| write_html!(f, <p>"failed"</p>)}>
+				<h1>"Items"</h1>
+				<Nested log={log.clone()} />
+				<Item name="second" polls={1} log={log} />
+			</ErrorBoundary>
+		</main>
+	);
+
+	assert_eq!(
+		block_on(html.render_async().unwrap().into_bytes()).unwrap(),
+		"<main><h1>Items</h1><section><li>inner</li></section><li>second</li></main>"
+	);
+}
+
+#[test]
+fn deferred_error_caught_by_error_boundary() {
+	let log = Arc::new(Mutex::new(Vec::new()));
+	let errors = RefCell::new(0);
+	let html = html_ref!(
+		<main>
+			<ErrorBoundary
+				fallback={|f, error| write_html!(f, <p>{error.to_string()}</p>)}
+				on_error={|_| *errors.borrow_mut() += 1}
+			>
+				<Item name="discarded" polls={1} log={log.clone()} />
+				<Broken />
+			</ErrorBoundary>
+			<footer />
+		</main>
+	);
+
+	assert_eq!(
+		block_on(html.render_async().unwrap().into_bytes()).unwrap(),
+		"<main><p>an error occurred while rendering</p><footer></footer></main>"
+	);
+	// the error happens after the boundary has been written
+	assert_eq!(errors.into_inner(), 0);
+}
+
+#[test]
+fn nested_deferred_error_caught_by_error_boundary() {
+	#[component(Outer)]
+	async fn outer() -> impl HtmlContent {
+		html!(<section><Broken /></section>)
+	}
+
+	let html = html!(
+		<ErrorBoundary fallback={|f, _| write_html!(f, <p>"failed"</p>)}>
+			<Outer />
+		</ErrorBoundary>
+	);
+
+	assert_eq!(
+		block_on(html.render_async().unwrap().into_bytes()).unwrap(),
+		"<p>failed</p>"
+	);
+}
+
+#[test]
+fn deferred_error_after_output() {
+	let mut render = html!(<main><Broken /></main>).render_async().unwrap();
+	let mut cx = Context::from_waker(Waker::noop());
+
+	assert_eq!(
+		render.poll_chunk(&mut cx),
+		Poll::Ready(Some(Ok(bytes::Bytes::from_static(b"<main>"))))
+	);
+	assert_eq!(
+		render.poll_chunk(&mut cx),
+		Poll::Ready(Some(Err(std::fmt::Error)))
+	);
+}
+
+#[test]
+fn suspense_awaited_in_place() {
+	let log = Arc::new(Mutex::new(Vec::new()));
+	let html = html!(
+		<main>
+			<Suspense fallback={html!(<p>"Loading"</p>)}>
+				{async move { html!(<ul><Item name="loaded" polls={1} log={log} /></ul>) }}
+			</Suspense>
+		</main>
+	);
+
+	assert_eq!(
+		block_on(html.render_async().unwrap().into_bytes()).unwrap(),
+		"<main><ul><li>loaded</li></ul></main>"
+	);
+}
+
+#[test]
+fn async_content_streamed_with_suspense() {
+	let log = Arc::new(Mutex::new(Vec::new()));
+	let html = html!(
+		<main>
+			<Item name="page" polls={1} log={log.clone()} />
+			<Suspense fallback={html!(<p>"Loading"</p>)}>
+				{async move { html!(<ul><Item name="chunk" polls={1} log={log} /></ul>) }}
+			</Suspense>
+		</main>
+	);
+
+	let bytes = block_on(html.render_streaming().unwrap().into_bytes()).unwrap();
+	assert!(std::str::from_utf8(&bytes).unwrap().starts_with(concat!(
+		"<main><li>page</li><rstml-suspense id=\"rstml-suspense-0\"><p>Loading</p></rstml-suspense></main>",
+		"<template id=\"rstml-suspense-content-0\"><ul><li>chunk</li></ul></template><script>",
+	)));
+}
@@ -1,5 +1,8 @@
 use bytes::BytesMut;
-use rstml_component::{write_html, For, HtmlFormatter};
+use rstml_component::{
+	html, write_html, CaughtError, ErrorBoundary, For, HtmlFormatter, Iteration, Match, Show, Switch,
+};
+use std::cell::RefCell;
 
 macro_rules! assert_html_eq {
 	($expected:expr, $($rest:tt)*) => {
@@ -26,3 +29,199 @@ fn for_iter() {
 		</ul>
 	);
 }
+
+#[test]
+fn for_separator() {
+	let items = ["a", "b", "c"];
+
+	assert_html_eq!(
+		"<p>a, b, c</p>",
+		<p>
+			<For items={items} separator=", ">
+				{|f, item| f.write_content(item)}
+			</For>
+		</p>
+	);
+}
+
+#[test]
+fn for_empty() {
+	let items: Vec<&str> = Vec::new();
+
+	assert_html_eq!(
+		"<ul><li>No items</li></ul>",
+		<ul>
+			<For items={items} separator={html!(<hr>)} empty={html!(<li>"No items"</li>)}>
+				{|f, item| write_html!(f, <li>{item}</li>)}
+			</For>
+		</ul>
+	);
+}
+
+#[test]
+fn for_iteration() {
+	let items = ["a", "b", "c"];
+
+	assert_html_eq!(
+		"<li class=\"first\">0a</li><li class=\"odd\">1b</li><li class=\"last\">2c</li>",
+		<For items={Iteration::enumerate(items)}>
+			{|f, (iteration, item): (Iteration, &str)| write_html!(f,
+				<li class:first={iteration.is_first()} class:odd={iteration.is_odd()} class:last={iteration.is_last()}>
+					{iteration.index()}{item}
+				</li>
+			)}
+		</For>
+	);
+}
+
+#[test]
+fn iteration_enumerate() {
+	let iterations: Vec<_> = Iteration::enumerate(["a", "b"])
+		.map(|(iteration, item)| {
+			(
+				item,
+				iteration.index(),
+				iteration.is_first(),
+				iteration.is_last(),
+				iteration.is_even(),
+			)
+		})
+		.collect();
+
+	assert_eq!(
+		iterations,
+		[("a", 0, true, false, true), ("b", 1, false, true, false)]
+	);
+	assert_eq!(Iteration::enumerate(Vec::<u8>::new()).next(), None);
+}
+
+#[test]
+fn show() {
+	let visible = true;
+
+	assert_html_eq!(
+		"<p>Shown</p>",
+		<Show when={visible}><p>"Shown"</p></Show>
+		<Show when={!visible}><p>"Hidden"</p></Show>
+	);
+}
+
+#[test]
+fn show_fallback() {
+	let items: Vec<u32> = Vec::new();
+
+	assert_html_eq!(
+		"<p>No items</p>",
+		<Show when={|| !items.is_empty()} fallback={html!(<p>"No items"</p>)}>
+			<p>{items.len()}" items"</p>
+		</Show>
+	);
+}
+
+#[test]
+fn switch_first_match() {
+	let value = 7;
+
+	assert_html_eq!(
+		"<span>small</span>",
+		<Switch>
+			<Match when={value == 0}><span>"zero"</span></Match>
+			<Match when={value < 10}><span>"small"</span></Match>
+			<Match when={|| panic!("later cases are not tested")}><span>"odd"</span></Match>
+		</Switch>
+	);
+}
+
+#[test]
+fn switch_fallback() {
+	let value = 42;
+
+	assert_html_eq!(
+		"<span>large</span>",
+		<Switch fallback={html!(<span>"large"</span>)}>
+			<Match when={value == 0}><span>"zero"</span></Match>
+			<Match when={value < 10}><span>"small"</span></Match>
+		</Switch>
+	);
+}
+
+#[test]
+fn switch_nested() {
+	let (outer, inner) = (1, 2);
+
+	assert_html_eq!(
+		"<p>one</p><p>two</p><p>after</p>",
+		<Switch>
+			<Match when={outer == 1}>
+				<p>"one"</p>
+				<Switch fallback={html!(<p>"none"</p>)}>
+					<Match when={inner == 2}><p>"two"</p></Match>
+				</Switch>
+			</Match>
+			<Match when={outer == 1}><p>"duplicate"</p></Match>
+		</Switch>
+		<Match when={true}><p>"after"</p></Match>
+	);
+}
+
+fn broken(_: &mut HtmlFormatter) -> std::fmt::Result {
+	Err(std::fmt::Error)
+}
+
+#[test]
+fn error_boundary_renders_children() {
+	assert_html_eq!(
+		"<main><p>fine</p></main>",
+		<main>
+			<ErrorBoundary fallback={|f, _| write_html!(f, <p>"fallback"</p>)}>
+				<p>"fine"</p>
+			</ErrorBoundary>
+		</main>
+	);
+}
+
+#[test]
+fn error_boundary_discards_partial_output() {
+	let errors = RefCell::new(Vec::new());
+
+	assert_html_eq!(
+		"<main><p>an error occurred while rendering</p><footer></footer></main>",
+		<main>
+			<ErrorBoundary
+				fallback={|f, error| write_html!(f, <p>{error.to_string()}</p>)}
+				on_error={|error| errors.borrow_mut().push(error.clone())}
+			>
+				<section class="widget"><p>"partial"</p>{broken}</section>
+			</ErrorBoundary>
+			<footer />
+		</main>
+	);
+
+	assert_eq!(errors.into_inner(), [CaughtError::Error(std::fmt::Error)]);
+}
+
+#[test]
+fn error_boundary_without_fallback() {
+	assert_html_eq!(
+		"<main></main>",
+		<main>
+			<ErrorBoundary><p>{broken}</p></ErrorBoundary>
+		</main>
+	);
+}
+
+#[test]
+fn error_boundary_catches_panics() {
+	fn panics(_: &mut HtmlFormatter) -> std::fmt::Result {
+		panic!("widget exploded")
+	}
+
+	assert_html_eq!(
+		"<div><p>rendering panicked: widget exploded</p></div>",
+		<div>
+			<ErrorBoundary catch_panics={true} fallback={|f, error| write_html!(f, <p>{error.to_string()}</p>)}>
+				<div class:open={true}>{panics}</div>
+			</ErrorBoundary>
+		</div>
+	);
+}
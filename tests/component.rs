@@ -1,4 +1,6 @@
-use rstml_component::{write_html, HtmlAttributeValue, HtmlComponent, HtmlContent, HtmlFormatter};
+use rstml_component::{
+	write_html, For, HtmlAttributeValue, HtmlAttributes, HtmlComponent, HtmlContent, HtmlFormatter,
+};
 
 #[derive(HtmlComponent)]
 struct NavBar;
@@ -133,3 +135,297 @@ fn test_output() {
 
 	assert_eq!(output, expected);
 }
+
+#[derive(HtmlComponent, Clone)]
+struct Button<A>
+where
+	A: HtmlAttributes,
+{
+	label: &'static str,
+	kind: &'static str,
+	attrs: A,
+}
+
+impl<A> HtmlContent for Button<A>
+where
+	A: HtmlAttributes,
+{
+	fn fmt(self, formatter: &mut HtmlFormatter) -> std::fmt::Result {
+		write_html!(formatter,
+			<button class=self.kind ..self.attrs>{self.label}</button>
+		)
+	}
+}
+
+#[test]
+fn prop_spread() {
+	let base = Button {
+		label: "Cancel",
+		kind: "primary",
+		attrs: (),
+	};
+
+	let label = "Delete";
+	let output = (|formatter: &mut HtmlFormatter| {
+		write_html!(formatter,
+			<Button ..base.clone() label="Save" />
+			<Button label=label ..base.clone() />
+			<Button ..base />
+		)
+	})
+	.into_string()
+	.expect("formatting works and produces valid utf-8");
+
+	assert_eq!(
+		output,
+		r#"<button class="primary">Save</button><button class="primary">Delete</button><button class="primary">Cancel</button>"#
+	);
+}
+
+#[test]
+fn forwarded_attributes() {
+	let id = 42;
+	let output = (|formatter: &mut HtmlFormatter| {
+		write_html!(formatter,
+			<Button label="Save" kind="primary" attr:data-id=id attr:disabled />
+		)
+	})
+	.into_string()
+	.expect("formatting works and produces valid utf-8");
+
+	assert_eq!(
+		output,
+		r#"<button class="primary" data-id="42" disabled>Save</button>"#
+	);
+}
+
+#[test]
+fn forwarded_attributes_are_merged() {
+	let output = (|formatter: &mut HtmlFormatter| {
+		write_html!(formatter,
+			<Button label="Save" kind="primary" attr:class="wide" attr:type="submit" />
+		)
+	})
+	.into_string()
+	.expect("formatting works and produces valid utf-8");
+
+	assert_eq!(
+		output,
+		r#"<button class="primary wide" type="submit">Save</button>"#
+	);
+}
+
+#[derive(HtmlComponent)]
+struct Card<'a> {
+	#[html(into)]
+	title: String,
+	#[html(default = "card")]
+	class: &'a str,
+	#[html(optional)]
+	footer: Option<&'a str>,
+}
+
+impl HtmlContent for Card<'_> {
+	fn fmt(self, formatter: &mut HtmlFormatter) -> std::fmt::Result {
+		write_html!(formatter,
+			<div class=self.class>
+				<h2>{self.title}</h2>
+				{self.footer}
+			</div>
+		)
+	}
+}
+
+#[test]
+fn builder() {
+	let output = Card::builder()
+		.title("First")
+		.build()
+		.into_string()
+		.expect("formatting works and produces valid utf-8");
+
+	assert_eq!(output, r#"<div class="card"><h2>First</h2></div>"#);
+
+	let output = Card::builder()
+		.footer("Footer")
+		.class("card wide")
+		.title(String::from("Second"))
+		.build()
+		.into_string()
+		.expect("formatting works and produces valid utf-8");
+
+	assert_eq!(
+		output,
+		r#"<div class="card wide"><h2>Second</h2>Footer</div>"#
+	);
+}
+
+#[derive(HtmlComponent)]
+struct Job {
+	builder: &'static str,
+	#[html(default = "queued")]
+	state: &'static str,
+}
+
+impl HtmlContent for Job {
+	fn fmt(self, formatter: &mut HtmlFormatter) -> std::fmt::Result {
+		write_html!(formatter, <p>{self.builder}" "{self.state}</p>)
+	}
+}
+
+#[test]
+fn builder_prop() {
+	let output = Job::builder()
+		.builder("nightly")
+		.build()
+		.into_string()
+		.expect("formatting works and produces valid utf-8");
+
+	assert_eq!(output, "<p>nightly queued</p>");
+}
+
+#[test]
+fn builder_in_templates() {
+	let output = (|formatter: &mut HtmlFormatter| {
+		write_html!(formatter,
+			<Card title="First" .. />
+			<Card title="Second" footer="Footer" .. />
+		)
+	})
+	.into_string()
+	.expect("formatting works and produces valid utf-8");
+
+	assert_eq!(
+		output,
+		r#"<div class="card"><h2>First</h2></div><div class="card"><h2>Second</h2>Footer</div>"#
+	);
+}
+
+struct Manual {
+	name: &'static str,
+}
+
+impl HtmlComponent for Manual {
+	type Content = Self;
+
+	fn into_content(self) -> Self::Content {
+		self
+	}
+}
+
+impl HtmlContent for Manual {
+	fn fmt(self, formatter: &mut HtmlFormatter) -> std::fmt::Result {
+		write_html!(formatter, <p>"Manual "{self.name}</p>)
+	}
+}
+
+struct Plain {
+	name: &'static str,
+}
+
+impl HtmlContent for Plain {
+	fn fmt(self, formatter: &mut HtmlFormatter) -> std::fmt::Result {
+		write_html!(formatter, <p>"Plain "{self.name}</p>)
+	}
+}
+
+#[test]
+fn hand_written_components() {
+	let name = "component";
+	let output = (|formatter: &mut HtmlFormatter| {
+		write_html!(formatter,
+			<Manual name={name} />
+			<Plain name="content" />
+		)
+	})
+	.into_string()
+	.expect("formatting works and produces valid utf-8");
+
+	assert_eq!(output, r#"<p>Manual component</p><p>Plain content</p>"#);
+}
+
+#[derive(HtmlComponent, HtmlContent)]
+enum Status<'a> {
+	#[html(template = { <span class="badge">"Online"</span> })]
+	Online,
+	#[html(template = { <span class="badge away">"Away since "{since}</span> })]
+	Away {
+		since: &'a str,
+		reason: &'a str,
+	},
+	#[html(template = { <span class="badge" title=_1>{_0}</span> })]
+	Custom(&'a str, &'a str),
+	Card(Card<'a>),
+}
+
+#[test]
+fn enum_content() {
+	let statuses = [
+		Status::Online,
+		Status::Away {
+			since: "noon",
+			reason: "lunch",
+		},
+		Status::Custom("Busy", "In a meeting"),
+		Status::Card(Card::builder().title("Offline").build()),
+	];
+
+	let output = (|formatter: &mut HtmlFormatter| {
+		write_html!(formatter,
+			<For items={statuses}>
+				{ |formatter, status| formatter.write_content(status) }
+			</For>
+		)
+	})
+	.into_string()
+	.expect("formatting works and produces valid utf-8");
+
+	assert_eq!(
+		output,
+		r#"<span class="badge">Online</span><span class="badge away">Away since noon</span><span class="badge" title="In a meeting">Busy</span><div class="card"><h2>Offline</h2></div>"#
+	);
+}
+
+#[derive(HtmlComponent)]
+struct UserName(#[html(into)] String);
+
+impl HtmlContent for UserName {
+	fn fmt(self, formatter: &mut HtmlFormatter) -> std::fmt::Result {
+		write_html!(formatter, <span class="user">"@"{self.0}</span>)
+	}
+}
+
+#[derive(HtmlComponent)]
+struct Price(u32, #[html(default = "USD")] &'static str);
+
+impl HtmlContent for Price {
+	fn fmt(self, formatter: &mut HtmlFormatter) -> std::fmt::Result {
+		write_html!(formatter, <span class="price">{self.0}" "{self.1}</span>)
+	}
+}
+
+#[test]
+fn tuple_components() {
+	let output = (|formatter: &mut HtmlFormatter| {
+		write_html!(formatter,
+			<UserName value="alice" .. />
+			<Price _0=42 .. />
+			<Price _0=7 _1="EUR" .. />
+		)
+	})
+	.into_string()
+	.expect("formatting works and produces valid utf-8");
+
+	assert_eq!(
+		output,
+		r#"<span class="user">@alice</span><span class="price">42 USD</span><span class="price">7 EUR</span>"#
+	);
+
+	let output = UserName::builder()
+		.value("bob")
+		.build()
+		.into_string()
+		.expect("formatting works and produces valid utf-8");
+
+	assert_eq!(output, r#"<span class="user">@bob</span>"#);
+}
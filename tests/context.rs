@@ -0,0 +1,125 @@
+use rstml_component::{
+	component, html, write_html, AsyncHtmlContent, ContextStore, HtmlContent, HtmlFormatter, Provider,
+};
+use std::{
+	future::Future,
+	pin::pin,
+	sync::Arc,
+	task::{Context, Poll, Waker},
+};
+
+struct Locale(&'static str);
+
+struct CurrentUser {
+	name: &'static str,
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+	let mut future = pin!(future);
+	let mut cx = Context::from_waker(Waker::noop());
+	loop {
+		if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+			return output;
+		}
+	}
+}
+
+fn locale(f: &mut HtmlFormatter) -> std::fmt::Result {
+	let locale = f.context::<Locale>().map_or("none", |locale| locale.0);
+	f.write_content(locale)
+}
+
+#[test]
+fn provide_is_scoped() {
+	let html = html!(
+		<p>{locale}</p>
+		{|f: &mut HtmlFormatter| f.provide(Locale("en"), html!(
+			<p>{locale}</p>
+			<Provider value={Locale("nl")}><p>{locale}</p></Provider>
+			<p>{locale}</p>
+		))}
+		<p>{locale}</p>
+	);
+
+	assert_eq!(
+		html.into_string().unwrap(),
+		"<p>none</p><p>en</p><p>nl</p><p>en</p><p>none</p>"
+	);
+}
+
+#[test]
+fn with_context() {
+	let mut context = ContextStore::new();
+	context.insert(Locale("de"));
+
+	let html = html!(<p>{|f: &mut HtmlFormatter| f.with_context(context, locale)}</p>);
+	assert_eq!(html.into_string().unwrap(), "<p>de</p>");
+}
+
+#[component(Greeting)]
+fn greeting(
+	#[prop(default = "Hello")] greeting: &'static str,
+	#[context] user: Arc<CurrentUser>,
+	#[context] locale: Option<Arc<Locale>>,
+) -> impl HtmlContent {
+	let locale = locale.map_or("none", |locale| locale.0);
+	html!(<p lang=locale>{greeting}", "{user.name}</p>)
+}
+
+#[test]
+fn component_context_arguments() {
+	let html = html!(
+		<Provider value={CurrentUser { name: "alice" }}>
+			<Greeting .. />
+			<Provider value={Locale("fr")}><Greeting greeting="Bonjour" /></Provider>
+		</Provider>
+	);
+
+	assert_eq!(
+		html.into_string().unwrap(),
+		r#"<p lang="none">Hello, alice</p><p lang="fr">Bonjour, alice</p>"#
+	);
+}
+
+#[test]
+fn missing_required_context() {
+	assert!(html!(<Greeting .. />).into_string().is_err());
+}
+
+#[component(AsyncGreeting)]
+async fn async_greeting(#[context] user: Arc<CurrentUser>) -> impl HtmlContent {
+	html!(<p>"Hello, "{user.name}" "{locale}</p>)
+}
+
+#[test]
+fn async_content_keeps_context() {
+	let page = async {
+		html!(
+			<Provider value={Locale("en")}>
+				<Provider value={CurrentUser { name: "bob" }}>
+					<AsyncGreeting />
+				</Provider>
+			</Provider>
+			<footer>{locale}</footer>
+		)
+	};
+
+	assert_eq!(
+		block_on(page.into_string_async()).unwrap(),
+		"<p>Hello, bob en</p><footer>none</footer>"
+	);
+}
+
+#[test]
+fn write_html_context() {
+	let mut buffer = bytes::BytesMut::new();
+	let mut formatter = HtmlFormatter::new(&mut buffer);
+	formatter
+		.provide(Locale("it"), |f: &mut HtmlFormatter| {
+			let locale = f.context::<Locale>().unwrap();
+			write_html!(f, <html lang=locale.0></html>)
+		})
+		.unwrap();
+
+	assert_eq!(buffer, r#"<html lang="it"></html>"#);
+}
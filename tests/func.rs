@@ -107,3 +107,29 @@ fn mixed_args() {
 
 	assert_eq!(html, expected);
 }
+
+#[test]
+fn prop_attributes() {
+	#[component(Greeting)]
+	fn greeting(
+		#[prop(into)] name: String,
+		#[prop(default = "Hello")] greeting: &'static str,
+		#[prop(default)] punctuation: String,
+		#[prop(optional)] title: Option<&'static str>,
+	) -> impl HtmlContent {
+		html! {
+			<p title=title.unwrap_or("none")>{greeting}", "{name}{punctuation}</p>
+		}
+	}
+
+	let html = html! {
+		<Greeting name="World" .. />
+		<Greeting name="there" greeting="Hi" punctuation="!".to_string() title="greeting" .. />
+	}
+	.into_string()
+	.expect("formatting works and produces valid utf-8");
+
+	let expected = r#"<p title="none">Hello, World</p><p title="greeting">Hi, there!</p>"#;
+
+	assert_eq!(html, expected);
+}
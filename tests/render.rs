@@ -0,0 +1,95 @@
+use rstml_component::{
+	html, html_ref, write_html, HtmlComponent, HtmlContent, HtmlFormatter, HtmlRender,
+};
+
+struct User {
+	name: String,
+	age: u32,
+}
+
+struct UserRow<'a> {
+	user: &'a User,
+}
+
+impl HtmlRender for User {
+	fn render(&self, formatter: &mut HtmlFormatter) -> std::fmt::Result {
+		write_html!(formatter, <span class="user">{&self.name}" ("{&self.age}")"</span>)
+	}
+}
+
+#[derive(HtmlComponent)]
+struct Card<C: HtmlContent> {
+	children: C,
+}
+
+impl<C: HtmlContent> HtmlContent for Card<C> {
+	fn fmt(self, formatter: &mut HtmlFormatter) -> std::fmt::Result {
+		write_html!(formatter, <div class="card">{self.children}</div>)
+	}
+}
+
+impl<'a> HtmlContent for UserRow<'a> {
+	fn fmt(self, formatter: &mut HtmlFormatter) -> std::fmt::Result {
+		write_html!(formatter, <tr><td>{self.user.as_content()}</td></tr>)
+	}
+}
+
+#[test]
+fn render_repeatedly() {
+	let title = String::from("Users");
+	let header = html_ref!(<h1>{&title}</h1>);
+
+	assert_eq!(header.render_to_string().unwrap(), "<h1>Users</h1>");
+	assert_eq!(header.render_to_string().unwrap(), "<h1>Users</h1>");
+
+	let page = html!(
+		<main>
+			{&header}
+			<Card>{&header}</Card>
+		</main>
+	);
+
+	assert_eq!(
+		page.into_string().unwrap(),
+		r#"<main><h1>Users</h1><div class="card"><h1>Users</h1></div></main>"#
+	);
+
+	// the title is only borrowed
+	assert_eq!(title, "Users");
+}
+
+#[test]
+fn render_borrowed_data() {
+	let users = vec![
+		User {
+			name: "alice".into(),
+			age: 42,
+		},
+		User {
+			name: "bob".into(),
+			age: 7,
+		},
+	];
+
+	let table = html_ref!(
+		<table>
+			{|f: &mut HtmlFormatter| {
+				for user in &users {
+					f.write_content(UserRow { user })?;
+				}
+				Ok(())
+			}}
+		</table>
+	);
+
+	let expected = r#"<table><tr><td><span class="user">alice (42)</span></td></tr><tr><td><span class="user">bob (7)</span></td></tr></table>"#;
+	assert_eq!(table.render_to_string().unwrap(), expected);
+	assert_eq!(table.render_to_string().unwrap(), expected);
+	assert_eq!(
+		users[0].render_to_string().unwrap(),
+		r#"<span class="user">alice (42)</span>"#
+	);
+
+	let missing: Option<User> = None;
+	assert_eq!(html!({&missing}{&users[1].age}).into_string().unwrap(), "7");
+}
@@ -0,0 +1,139 @@
+use bytes::BytesMut;
+use rstml_component::{
+	html, write_html, ErrorBoundary, HtmlContent, HtmlFormatter, Suspense, SuspenseQueue,
+};
+use std::{
+	future::Future,
+	pin::Pin,
+	task::{Context, Poll, Waker},
+};
+
+/// A future that is pending for the given number of polls.
+struct Delay(usize);
+
+impl Future for Delay {
+	type Output = ();
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+		if self.0 == 0 {
+			return Poll::Ready(());
+		}
+
+		self.0 -= 1;
+		cx.waker().wake_by_ref();
+		Poll::Pending
+	}
+}
+
+/// Renders the content, and then each chunk of the suspense queue as it resolves.
+fn render_chunks(content: impl HtmlContent) -> Vec<String> {
+	let mut buffer = BytesMut::new();
+	let mut queue = SuspenseQueue::new();
+	content
+		.fmt(&mut HtmlFormatter::with_suspense(&mut buffer, &mut queue))
+		.expect("failed to write html");
+
+	let mut chunks = vec![String::from_utf8(buffer.to_vec()).unwrap()];
+	let mut cx = Context::from_waker(Waker::noop());
+	loop {
+		match queue.poll_chunk(&mut cx) {
+			Poll::Ready(Some(chunk)) => {
+				let chunk = chunk.expect("failed to write chunk");
+				chunks.push(String::from_utf8(chunk.to_vec()).unwrap());
+			}
+			Poll::Ready(None) => return chunks,
+			Poll::Pending => {}
+		}
+	}
+}
+
+fn chunk(id: usize, content: &str) -> String {
+	format!(
+		"<template id=\"rstml-suspense-content-{id}\">{content}</template><script>(function(){{var p=document.getElementById(\"rstml-suspense-{id}\"),t=document.getElementById(\"rstml-suspense-content-{id}\");if(p)p.replaceWith(t.content);t.remove();}})()</script>"
+	)
+}
+
+#[test]
+fn suspense_streams_out_of_order() {
+	let chunks = render_chunks(html!(
+		<main>
+			<Suspense fallback={html!(<p>"Loading slow"</p>)}>
+				{async {
+					Delay(3).await;
+					html!(<p>"slow"</p>)
+				}}
+			</Suspense>
+			<Suspense>
+				{async {
+					Delay(1).await;
+					html!(<p>"fast"</p>)
+				}}
+			</Suspense>
+			<footer />
+		</main>
+	));
+
+	assert_eq!(
+		chunks,
+		[
+			"<main><rstml-suspense id=\"rstml-suspense-0\"><p>Loading slow</p></rstml-suspense><rstml-suspense id=\"rstml-suspense-1\"></rstml-suspense><footer></footer></main>".to_owned(),
+			chunk(1, "<p>fast</p>"),
+			chunk(0, "<p>slow</p>"),
+		]
+	);
+}
+
+#[test]
+fn suspense_nested() {
+	let chunks = render_chunks(html!(
+		<Suspense>
+			{async {
+				html!(
+					<p>"outer"</p>
+					<Suspense fallback={html!("inner fallback")}>
+						{async { html!(<p>"inner"</p>) }}
+					</Suspense>
+				)
+			}}
+		</Suspense>
+	));
+
+	assert_eq!(
+		chunks,
+		[
+			"<rstml-suspense id=\"rstml-suspense-0\"></rstml-suspense>".to_owned(),
+			chunk(
+				0,
+				"<p>outer</p><rstml-suspense id=\"rstml-suspense-1\">inner fallback</rstml-suspense>"
+			),
+			chunk(1, "<p>inner</p>"),
+		]
+	);
+}
+
+#[test]
+fn suspense_without_queue() {
+	let html = html!(
+		<Suspense fallback={html!(<p>"Loading"</p>)}>
+			{async { html!(<p>"loaded"</p>) }}
+		</Suspense>
+	);
+
+	assert_eq!(html.into_string().unwrap(), "<p>Loading</p>");
+}
+
+#[test]
+fn suspense_discarded_by_error_boundary() {
+	fn broken(_: &mut HtmlFormatter) -> std::fmt::Result {
+		Err(std::fmt::Error)
+	}
+
+	let chunks = render_chunks(html!(
+		<ErrorBoundary fallback={|f, _| write_html!(f, <p>"failed"</p>)}>
+			<Suspense>{async { html!(<p>"discarded"</p>) }}</Suspense>
+			{broken}
+		</ErrorBoundary>
+	));
+
+	assert_eq!(chunks, ["<p>failed</p>"]);
+}
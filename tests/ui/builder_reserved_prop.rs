@@ -0,0 +1,14 @@
+use rstml_component::{HtmlComponent, HtmlContent, HtmlFormatter};
+
+#[derive(HtmlComponent)]
+struct Deploy {
+	build: u32,
+}
+
+impl HtmlContent for Deploy {
+	fn fmt(self, formatter: &mut HtmlFormatter) -> std::fmt::Result {
+		formatter.write_content(self.build)
+	}
+}
+
+fn main() {}
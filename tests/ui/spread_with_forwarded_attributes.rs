@@ -0,0 +1,22 @@
+use rstml_component::{html, HtmlAttributes, HtmlComponent, HtmlContent, HtmlFormatter};
+
+#[derive(HtmlComponent)]
+struct Button<A: HtmlAttributes> {
+	label: &'static str,
+	attrs: A,
+}
+
+impl<A: HtmlAttributes> HtmlContent for Button<A> {
+	fn fmt(self, formatter: &mut HtmlFormatter) -> std::fmt::Result {
+		formatter.write_content(self.label)
+	}
+}
+
+fn main() {
+	let base = Button {
+		label: "Cancel",
+		attrs: (),
+	};
+
+	let _ = html!(<Button ..base attr:disabled />);
+}
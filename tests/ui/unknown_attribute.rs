@@ -0,0 +1,19 @@
+use rstml_component::html;
+
+fn main() {
+	let _ = html! {
+		#![html(validate)]
+		<div clas="card">"Card"</div>
+	};
+
+	let _ = html! {
+		#![html(validate)]
+		<a hreflang="en" frobnicate="1">"Link"</a>
+	};
+
+	// attributes of other elements aren't allowed
+	let _ = html! {
+		#![html(validate)]
+		<div href="/">"Card"</div>
+	};
+}
@@ -0,0 +1,13 @@
+use rstml_component::html;
+
+fn main() {
+	let _ = html! {
+		#![html(validate)]
+		<dvi class="card">"Card"</dvi>
+	};
+
+	let _ = html! {
+		#![html(validate)]
+		<frobnicate />
+	};
+}
@@ -1,5 +1,8 @@
 use bytes::BytesMut;
-use rstml_component::{write_html, HtmlFormatter};
+use rstml_component::{
+	static_html, write_html, ElementConfig, HtmlAttributeValue, HtmlAttributes, HtmlContent,
+	HtmlFormatter, RawText, StaticHtml,
+};
 
 macro_rules! assert_html_eq {
 	($expected:expr, $($rest:tt)*) => {
@@ -111,3 +114,474 @@ fn kitchen_sink() {
 		</html>
 	);
 }
+
+#[test]
+fn derived_attributes() {
+	#[derive(HtmlAttributes)]
+	struct Data {
+		data_user_id: u32,
+		#[html(skip)]
+		#[allow(dead_code)]
+		internal: u32,
+	}
+
+	#[derive(HtmlAttributes)]
+	struct Attributes {
+		id: &'static str,
+		#[html(rename = "class")]
+		class_name: Option<&'static str>,
+		title: Option<&'static str>,
+		hidden: bool,
+		required: Option<bool>,
+		#[html(as_value)]
+		aria_pressed: bool,
+		#[html(flatten)]
+		data: Data,
+	}
+
+	let attributes = Attributes {
+		id: "profile",
+		class_name: Some("card"),
+		title: None,
+		hidden: true,
+		required: Some(false),
+		aria_pressed: true,
+		data: Data {
+			data_user_id: 42,
+			internal: 7,
+		},
+	};
+
+	assert_html_eq!(
+		r#"<div id="profile" class="card" hidden aria-pressed="true" data-user-id="42"></div>"#,
+		<div {attributes} />
+	);
+}
+
+#[test]
+fn derived_attribute_value() {
+	#[derive(HtmlAttributeValue)]
+	enum Loading {
+		Eager,
+		Lazy,
+	}
+
+	#[derive(HtmlAttributeValue)]
+	enum ButtonKind {
+		PrimaryAction,
+		#[html(value = "btn \"danger\"")]
+		Danger,
+		Custom(String),
+	}
+
+	#[derive(HtmlAttributeValue)]
+	struct ClassName(&'static str);
+
+	assert_html_eq!(
+		r#"<img loading="lazy" /><img loading="eager" />"#,
+		<img loading=Loading::Lazy><img loading=Loading::Eager>
+	);
+
+	assert_html_eq!(
+		r#"<button class="primary-action"></button><button class="btn &quot;danger&quot;"></button><button class="other"></button>"#,
+		<button class=ButtonKind::PrimaryAction />
+		<button class=ButtonKind::Danger />
+		<button class=ButtonKind::Custom("other".into()) />
+	);
+
+	assert_html_eq!(r#"<p class="lead"></p>"#, <p class=ClassName("lead") />);
+}
+
+#[test]
+fn static_output() {
+	let dynamic = "<b>";
+	assert_html_eq!(
+		r#"<nav title="&quot;Main&quot; &amp; more"><!--a &lt; b--><a href="/">Home</a>&lt;b&gt;<hr /></nav>"#,
+		<nav title="\"Main\" & more">
+			<!-- "a < b" -->
+			<a href="/">Home</a>
+			{dynamic}
+			<hr />
+		</nav>
+	);
+}
+
+#[test]
+fn validated_names() {
+	assert_html_eq!(
+		r#"<div class="x" data-id="1" aria-label="y" onclick="z" hx-get="/"><my-element any="1"></my-element><svg viewBox="0 0 1 1"><path d=""></path></svg></div>"#,
+		#![html(validate, allow_attributes("hx-*"))]
+		<div class="x" data-id="1" aria-label="y" onclick="z" hx-get="/">
+			<my-element any="1" />
+			<svg viewBox="0 0 1 1"><path d="" /></svg>
+		</div>
+	);
+}
+
+#[test]
+fn validated_names_with_allowed_names() {
+	assert_html_eq!(
+		r#"<app hello="world"><p hello="there">Hi</p></app>"#,
+		#![html(validate, allow_elements("app"), allow_attributes("hello"))]
+		<app hello="world">
+			<p hello="there">"Hi"</p>
+		</app>
+	);
+}
+
+#[test]
+fn configured_elements() {
+	assert_html_eq!(
+		r#"<my-icon name="star" /><link rel="alternate"></link><textarea>a &lt; b</textarea><script>a < b</script>"#,
+		#![html(void_elements("my-icon"), non_void_elements("link"), raw_text_elements("textarea"))]
+		<my-icon name="star">
+		<link rel="alternate"></link>
+		<textarea>a < b</textarea>
+		<script>a < b</script>
+	);
+}
+
+#[test]
+fn configured_elements_at_runtime() {
+	let icon = |formatter: &mut HtmlFormatter| {
+		formatter.write_element(b"my-icon", ("name", "star"), ())?;
+		formatter.write_element(b"textarea", (), "a < b</p>")
+	};
+
+	assert_html_eq!(
+		r#"<div><my-icon name="star" /><textarea>a &lt; b&lt;/p&gt;</textarea></div>"#,
+		#![html(void_elements("my-icon"), raw_text_elements("textarea"))]
+		<div>{icon}</div>
+	);
+
+	assert_html_eq!(
+		r#"<div><my-icon name="star"></my-icon><textarea>a &lt; b&lt;/p&gt;</textarea></div>"#,
+		<div>{icon}</div>
+	);
+}
+
+#[test]
+fn whitespace() {
+	let name = "alice";
+	assert_html_eq!(
+		"<p>Visit example.com today</p><p>&copy; 2024</p><p> a\n  b </p>",
+		<p>Visit example.com today</p>
+		<p>&copy; 2024</p>
+		<p>" a\n  b "</p>
+	);
+
+	assert_html_eq!(
+		"<p>Visit example.com today, ok? a-b alice.</p><p>Hello alice, how are you? <b>fine</b> thanks</p>",
+		#![html(whitespace = "collapse")]
+		<p>Visit example.com today, ok? a-b {name}.</p>
+		<p>Hello {name}, how are you? "  " <b>fine</b> thanks</p>
+	);
+
+	assert_html_eq!(
+		"<p>Hello alice, a b</p><pre> a\n  b </pre><p>f(x, [1, 2]) -&gt; y</p>",
+		#![html(whitespace = "collapse")]
+		<p>Hello {name}, "  a \n  b"</p>
+		<pre>" a\n  b "</pre>
+		<p>f(x, [1, 2]) -> y</p>
+	);
+
+	assert_html_eq!(
+		"<ul><li>One</li><li>Two<b>2</b></li></ul>",
+		#![html(whitespace = "trim")]
+		<ul>
+			<li>" One "</li>
+			<li>"Two " <b>" 2 "</b>"\n"</li>
+		</ul>
+	);
+
+	assert_html_eq!(
+		"<p>Hello alice<b>one two</b>three</p>",
+		#![html(whitespace = "jsx")]
+		<p>
+			Hello {name}
+			<b>"one
+				two"</b>
+			three
+		</p>
+	);
+
+	assert_html_eq!(
+		"<div><p>a b</p><code> x </code></div>",
+		#![html(whitespace(p = "collapse", "code" = "preserve"), whitespace = "trim")]
+		<div>
+			<p>"a   b"</p>
+			<code>" x "</code>
+		</div>
+	);
+}
+
+#[test]
+fn static_text_escaping() {
+	assert_html_eq!(
+		"<p>a &lt; b &amp; c</p><p>&copy; 2024 &#169; &#xA9; AT&amp;T</p><p>x &gt; y &amp; z &copy;</p>",
+		<p>"a < b & c"</p>
+		<p>"&copy; 2024 &#169; &#xA9; AT&T"</p>
+		<p>x > y & z &copy;</p>
+	);
+
+	assert_html_eq!(
+		r#"<script>if (a < b) { console.log("<\/script>") }</script>"#,
+		<script>if (a < b) { console.log("</script>") }</script>
+	);
+}
+
+#[test]
+fn static_templates() {
+	const NAV: StaticHtml = static_html!(
+		<nav class="main">
+			<a href="/">"Home & away"</a>
+			<hr>
+		</nav>
+	);
+
+	const EXPECTED: &str = r#"<nav class="main"><a href="/">Home &amp; away</a><hr /></nav>"#;
+	const _: () = assert!(NAV.len() == EXPECTED.len());
+
+	assert_eq!(NAV.as_str(), EXPECTED);
+	assert_eq!(
+		NAV.into_bytes().unwrap().as_ptr(),
+		NAV.as_bytes().as_ptr(),
+		"static html should not be copied"
+	);
+
+	assert_html_eq!(
+		format!("<header>{}</header>", EXPECTED),
+		<header>{NAV}</header>
+	);
+}
+
+#[test]
+fn optional_attributes() {
+	let value: Option<&str> = None;
+	let title = Some("Hello");
+	assert_html_eq!(
+		r#"<input title="Hello" /><input value="" />"#,
+		<input value=value title=title />
+		<input value={Some("")} placeholder={None::<String>} />
+	);
+
+	let lang = ("lang", None::<&str>);
+	assert_html_eq!(r#"<div id="main"></div>"#, <div id="main" {lang} />);
+}
+
+#[test]
+fn class_and_style_directives() {
+	let active = true;
+	let disabled = false;
+	let color = Some("red");
+	let width: Option<&str> = None;
+	assert_html_eq!(
+		r#"<a id="x" class="link active primary" href="/" style="margin: 0; color: red"></a>"#,
+		<a
+			id="x"
+			class="link"
+			class:active={active}
+			class:disabled={disabled}
+			href="/"
+			style="margin: 0;"
+			style:color={color}
+			style:width={width}
+			class:primary
+		/>
+	);
+
+	assert_html_eq!(
+		r#"<b></b><i class="a &quot;b&quot;"></i>"#,
+		<b class:active={disabled} style:color={width} />
+		<i class:active={disabled} class={"a \"b\""} />
+	);
+}
+
+#[test]
+fn runtime_elements() {
+	let elements = ElementConfig::html()
+		.with_void_elements(["my-icon"])
+		.without_raw_text_elements(["style"]);
+
+	let mut buffer = BytesMut::new();
+	let mut formatter = HtmlFormatter::with_elements(&mut buffer, &elements);
+	formatter
+		.write_element(b"my-icon", ("name", "star"), "ignored")
+		.expect("failed to write html");
+	formatter
+		.write_element(b"script", (), "if (a < b) {}</script>")
+		.expect("failed to write html");
+	formatter
+		.write_element(b"style", (), "a < b")
+		.expect("failed to write html");
+	formatter
+		.write_element(b"br", (), ())
+		.expect("failed to write html");
+	formatter
+		.write_element(b"textarea", (), "</textarea>")
+		.expect("failed to write html");
+
+	assert_eq!(
+		std::str::from_utf8(&buffer).expect("invalid utf-8"),
+		r#"<my-icon name="star" /><script>if (a < b) {}<\/script></script><style>a &lt; b</style><br /><textarea>&lt;/textarea&gt;</textarea>"#
+	);
+}
+
+#[test]
+fn duplicate_attributes() {
+	assert_html_eq!(
+		r#"<p class="a b" style="margin: 0; color: red"></p>"#,
+		<p class="a" style="margin: 0" class="b" style="color: red" />
+	);
+
+	let spread = ("class", "b");
+	assert_html_eq!(
+		r#"<p class="a b" id="x"></p><p class="b a" id="x"></p>"#,
+		<p class="a" id="x" {spread} />
+		<p {spread} id="x" class="a" />
+	);
+
+	let spread = ("id", "y");
+	assert_html_eq!(
+		r#"<p id="y" title="t"></p><p id="x" title="t"></p><p title="t" data-x="1"></p>"#,
+		<p id="x" title="t" {spread} />
+		<p {spread} id="x" title="t" />
+		<p title="t" {("title", None::<&str>)} data-x="1" />
+	);
+
+	let style = ("style", "color: red;");
+	let mut buffer = bytes::BytesMut::new();
+	let mut formatter = HtmlFormatter::new(&mut buffer);
+	formatter.write_open_tag_start(b"div");
+	formatter.write_attribute(b"hidden", ()).unwrap();
+	formatter.write_attributes(style).unwrap();
+	formatter.write_attribute(b"HIDDEN", "until-found").unwrap();
+	formatter.write_attributes(("style", "margin: 0")).unwrap();
+	formatter.write_open_tag_end();
+	assert_eq!(
+		&buffer[..],
+		br#"<div hidden="until-found" style="color: red; margin: 0">"#
+	);
+}
+
+#[test]
+fn spread_attributes_with_children() {
+	let (id, class) = ("y", "c");
+	assert_html_eq!(
+		r#"<div id="x"><span id="y"></span></div><div class="a">text<span class="c"></span></div>"#,
+		<div {("id", "x")}><span id=id></span></div>
+		<div {("class", "a")}>"text"<span class=class></span></div>
+	);
+
+	let json = RawText::new(r#"{"title":1}"#);
+	assert_html_eq!(
+		r#"<p data-json="[]" title="t"><b title="u"></b></p>"#,
+		<p data-json={json} {("title", "t")} {("data-json", "[]")}><b {("title", "u")} /></p>
+	);
+}
+
+#[test]
+fn alpine_attributes() {
+	let open = false;
+	assert_html_eq!(
+		r#"<div x-data="{ open: false }" @click.outside="open = false" :class="{ hidden: !open }" x-on:keydown.escape.prevent="open = false"></div>"#,
+		<div
+			x-data="{ open: false }"
+			@click.outside="open = false"
+			:class="{ hidden: !open }"
+			x-on:keydown.escape.prevent="open = false"
+		/>
+	);
+
+	assert_html_eq!(
+		r#"<input @input.debounce.500ms="search" :disabled="false" x-bind:value="query" />"#,
+		<input @input.debounce.500ms="search" :disabled={open.to_string()} x-bind:value="query" />
+	);
+
+	assert_html_eq!(
+		r#"<button @keydown.ctrl.k="open" x-transition:enter.duration.500ms x-on:click.prevent=""></button>"#,
+		<button "@keydown.ctrl.k"="open" "x-transition:enter.duration.500ms" "x-on:click.prevent"="" />
+	);
+
+	assert_html_eq!(
+		r#"<a @click="go" :href="url" x-show="open"></a>"#,
+		#![html(validate, allow_attributes("x-*"))]
+		<a @click="go" :href="url" x-show="open" />
+	);
+
+	let kind = "primary";
+	assert_html_eq!(
+		r#"<button :disabled="busy" class="primary" :class="{ active: open }" hidden :title="label"></button>"#,
+		<button :disabled="busy" class=kind :class="{ active: open }" hidden ":title"="label" />
+	);
+}
+
+#[test]
+fn statements() {
+	let items = [("apple", 3, 2), ("pear", 1, 5)];
+	assert_html_eq!(
+		r#"<ul><li>apple: 6</li><li>pear: 5</li></ul><p>2 items</p>"#,
+		{let count = items.len();}
+		<ul>
+			{|f: &mut HtmlFormatter| {
+				for (name, quantity, price) in items {
+					write_html!(f,
+						<li>
+							{let total = quantity * price;}
+							{name}": "{total}
+						</li>
+					)?;
+				}
+				Ok(())
+			}}
+		</ul>
+		<p>{count}" items"</p>
+	);
+
+	let name = "outer";
+	assert_html_eq!(
+		r#"<p>inner</p><p>outer</p>"#,
+		<p>{let name = "inner";}{name}</p>
+		<p>{name}</p>
+	);
+
+	let mut log = Vec::new();
+	assert_html_eq!(r#"<p>0</p>"#, {log.push("rendered");}<p>{log.len() - 1}</p>);
+	assert_eq!(log, ["rendered"]);
+}
+
+#[test]
+fn dynamic_comments() {
+	let node_id = 7;
+	let elapsed = std::time::Duration::from_millis(12);
+	assert_html_eq!(
+		r#"<!--rendered by 7 in 12ms--><p></p>"#,
+		<!-- rendered by {node_id} in {elapsed:?} -->
+		<p />
+	);
+
+	let user = "--><script>alert(1)</script>";
+	assert_html_eq!(
+		r#"<!--user: --&gt;&lt;script&gt;alert(1)&lt;/script&gt;, id 007 {}--><!--build 42-->"#,
+		<!-- "user: " {user} ", id " {node_id:03} " {}" -->
+		<!-- build 42 -->
+	);
+
+	assert_html_eq!(
+		r#"<!--see example.com, a-b 7.--><!--f(x) -&gt; 7 ok?-->"#,
+		<!-- see example.com, a-b {node_id}. -->
+		<!-- f(x) -> {node_id} ok? -->
+	);
+}
+
+#[test]
+fn stripped_comments() {
+	let node_id = 7;
+	assert_html_eq!(
+		r#"<p>a</p>"#,
+		#![html(strip_comments)]
+		<!-- "static" -->
+		<p>"a"</p>
+		<!-- rendered by {node_id} -->
+	);
+}